@@ -1,11 +1,11 @@
-use crate::{prelude::Component, util::TypeInfo};
+use crate::{component::ComponentInfo, prelude::Component};
 
 pub trait Bundle: Sized + 'static {
-    fn into_dyn_components(self) -> Vec<(TypeInfo, Box<dyn Component>)>;
+    fn into_dyn_components(self) -> Vec<(ComponentInfo, Box<dyn Component>)>;
 }
 
 impl Bundle for () {
-    fn into_dyn_components(self) -> Vec<(TypeInfo, Box<dyn Component>)> {
+    fn into_dyn_components(self) -> Vec<(ComponentInfo, Box<dyn Component>)> {
         vec![]
     }
 }
@@ -14,10 +14,10 @@ macro_rules! impl_bundle_tuple {
     ($($t:ident),*) => {
         #[allow(non_snake_case)]
         impl<$($t: Component),*> Bundle for ($($t,)*) {
-            fn into_dyn_components(self) -> Vec<(TypeInfo, Box<dyn Component>)> {
+            fn into_dyn_components(self) -> Vec<(ComponentInfo, Box<dyn Component>)> {
                 let ($($t,)*) = self;
                 vec![$(
-                    (TypeInfo::of::<$t>(), Box::new($t))
+                    (ComponentInfo::of::<$t>(), Box::new($t))
                 ),*]
             }
         }
@@ -0,0 +1,82 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock,
+};
+
+/// The world's change tick counter.
+///
+/// It advances every time a component is inserted or mutably dereferenced, and every time a handler
+/// fetches a [`Query`](crate::query::Query), so comparing against the tick a handler last ran at
+/// tells whether something happened since.
+#[derive(Debug, Default, Clone)]
+pub struct ChangeTick {
+    tick: Arc<AtomicU64>,
+    /// Held shared while a change is recorded and exclusively by [`ChangeTick::increment`], so a
+    /// change is never recorded at a tick older than one taken while it was being recorded.
+    recording: Arc<RwLock<()>>,
+}
+
+impl ChangeTick {
+    pub fn get(&self) -> u64 {
+        self.tick.load(Ordering::Acquire)
+    }
+
+    pub fn increment(&self) -> u64 {
+        let _recording = self.recording.write().unwrap();
+        self.tick.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    /// Advances the tick and marks `ticks` as changed at it, in one step as far as
+    /// [`ChangeTick::increment`] can tell.
+    pub fn record_change(&self, ticks: &ComponentTicks) -> u64 {
+        let _recording = self.recording.read().unwrap();
+        let tick = self.tick.fetch_add(1, Ordering::AcqRel) + 1;
+        ticks.set_changed(tick);
+        tick
+    }
+}
+
+/// When a component was added and last changed, in [`ChangeTick`]s.
+#[derive(Debug, Default)]
+pub struct ComponentTicks {
+    added: AtomicU64,
+    changed: AtomicU64,
+}
+
+impl ComponentTicks {
+    pub fn new(tick: u64) -> Self {
+        Self {
+            added: AtomicU64::new(tick),
+            changed: AtomicU64::new(tick),
+        }
+    }
+
+    pub fn added(&self) -> u64 {
+        self.added.load(Ordering::Acquire)
+    }
+
+    pub fn changed(&self) -> u64 {
+        self.changed.load(Ordering::Acquire)
+    }
+
+    pub fn is_added(&self, last_run: u64) -> bool {
+        self.added() > last_run
+    }
+
+    pub fn is_changed(&self, last_run: u64) -> bool {
+        self.changed() > last_run
+    }
+
+    pub fn set_changed(&self, tick: u64) {
+        self.changed.store(tick, Ordering::Release);
+    }
+}
+
+impl Clone for ComponentTicks {
+    fn clone(&self) -> Self {
+        Self {
+            added: AtomicU64::new(self.added()),
+            changed: AtomicU64::new(self.changed()),
+        }
+    }
+}
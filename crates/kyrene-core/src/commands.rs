@@ -0,0 +1,189 @@
+use std::{future::Future, sync::Arc};
+
+use crate::{
+    bundle::Bundle,
+    component::Component,
+    entity::Entity,
+    handler::{EventHandlerMeta, HandlerParam},
+    lock::Mutex,
+    util::SyncBoxFuture,
+    world::World,
+    world_handle::WorldHandle,
+};
+
+pub(crate) type Command =
+    Box<dyn for<'a> FnOnce(&'a mut World) -> SyncBoxFuture<'a, ()> + Send + Sync>;
+
+tokio::task_local! {
+    /// The queue of the handler batch the current task belongs to.
+    static BATCH_QUEUE: CommandQueue;
+}
+
+/// Runs `fut` with any [`Commands`] fetched inside it recording into `queue`.
+pub(crate) async fn batch_scope<F: Future>(queue: CommandQueue, fut: F) -> F::Output {
+    BATCH_QUEUE.scope(queue, fut).await
+}
+
+/// Structural changes recorded by [`Commands`] that haven't been applied to the [`World`] yet.
+///
+/// `None` once the queue was closed.
+#[derive(Clone)]
+pub(crate) struct CommandQueue(Arc<Mutex<Option<Vec<Command>>>>);
+
+impl Default for CommandQueue {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Some(Vec::new()))))
+    }
+}
+
+impl CommandQueue {
+    /// Queues `command`, or hands it back if the queue was already closed.
+    async fn push(&self, command: Command) -> Result<(), Command> {
+        match &mut *self.0.lock().await {
+            Some(commands) => {
+                commands.push(command);
+                Ok(())
+            }
+            None => Err(command),
+        }
+    }
+
+    /// Takes the queued commands, leaving the queue open for more.
+    pub(crate) async fn take(&self) -> Vec<Command> {
+        self.0
+            .lock()
+            .await
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Takes the queued commands for the last time. Anything pushed after is handed back.
+    pub(crate) async fn close(&self) -> Vec<Command> {
+        self.0.lock().await.take().unwrap_or_default()
+    }
+}
+
+/// Records structural changes to the [`World`] to be applied later, without taking the world's write lock.
+///
+/// Commands recorded by a handler are applied once every handler in its batch has finished,
+/// whether or not the event was awaited. Each batch applies only its own commands.
+///
+/// Commands created outside of a handler, or used after their batch finished, are applied at the
+/// start of the next [`WorldHandle::update`], or whenever [`WorldHandle::apply_commands`] is called.
+#[derive(Clone)]
+pub struct Commands {
+    world: WorldHandle,
+    queue: CommandQueue,
+}
+
+impl Commands {
+    pub async fn new(world: WorldHandle) -> Self {
+        let queue = match BATCH_QUEUE.try_with(CommandQueue::clone) {
+            Ok(queue) => queue,
+            Err(_) => world.world.read().await.command_queue(),
+        };
+        Self { world, queue }
+    }
+
+    /// Queues an arbitrary operation on the [`World`].
+    pub async fn add<F>(&self, command: F)
+    where
+        F: for<'a> FnOnce(&'a mut World) -> SyncBoxFuture<'a, ()> + Send + Sync + 'static,
+    {
+        if let Err(command) = self.queue.push(Box::new(command)).await {
+            // the batch already hit its sync point, so this waits for the world's
+            let queue = self.world.world.read().await.command_queue();
+            // the world's queue is never closed
+            let _ = queue.push(command).await;
+        }
+    }
+
+    /// Reserves a new entity. It can be used right away, but is only spawned once the commands are applied.
+    pub async fn entity(&self) -> Entity {
+        self.world.world.read().await.reserve_entity()
+    }
+
+    pub async fn spawn<T: Bundle>(&self, bundle: T) -> Entity {
+        let entity = self.entity().await;
+        self.insert_bundle(entity, bundle).await;
+        entity
+    }
+
+    pub async fn insert<T: Component>(&self, entity: Entity, component: T) {
+        self.add(move |world| {
+            Box::pin(async move {
+                world.insert(entity, component).await;
+            })
+        })
+        .await;
+    }
+
+    pub async fn insert_bundle<T: Bundle>(&self, entity: Entity, bundle: T) {
+        // bundles aren't necessarily `Send`, their components are
+        let components = bundle.into_dyn_components();
+        self.add(move |world| {
+            Box::pin(async move {
+                for (info, component) in components {
+                    world.insert_dyn(entity, info, component).await;
+                }
+            })
+        })
+        .await;
+    }
+
+    pub async fn remove<T: Component>(&self, entity: Entity) {
+        self.add(move |world| {
+            Box::pin(async move {
+                world.remove::<T>(entity).await;
+            })
+        })
+        .await;
+    }
+
+    pub async fn despawn(&self, entity: Entity) {
+        self.add(move |world| {
+            Box::pin(async move {
+                world.despawn(entity).await;
+            })
+        })
+        .await;
+    }
+
+    pub async fn insert_resource<T: Component>(&self, resource: T) {
+        self.add(move |world| {
+            Box::pin(async move {
+                world.insert_resource(resource).await;
+            })
+        })
+        .await;
+    }
+
+    pub async fn remove_resource<T: Component>(&self) {
+        self.add(move |world| {
+            Box::pin(async move {
+                world.remove_resource::<T>().await;
+            })
+        })
+        .await;
+    }
+}
+
+impl HandlerParam for Commands {
+    type Item = Commands;
+    type State = ();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
+
+    async fn init_state(_world: WorldHandle) -> Self::State {}
+
+    async fn fetch(world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(Commands::new(world).await)
+    }
+
+    async fn can_run(_world: WorldHandle, _: &()) -> bool {
+        true
+    }
+}
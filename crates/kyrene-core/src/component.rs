@@ -1,4 +1,5 @@
 use std::{
+    any::type_name,
     fmt::Debug,
     marker::PhantomData,
     ops::{Deref, DerefMut},
@@ -10,8 +11,11 @@ use itertools::Either;
 
 use crate::{
     bundle::Bundle,
+    change_detection::{ChangeTick, ComponentTicks},
+    diagnostics::{track_lock, Access, HeldLock, LockInfo},
     entity::{Entity, EntityMap, EntitySet},
     lock::{Read, RwLock, Write},
+    storage::{Column, ColumnWrite, StorageType},
     util::{TypeIdMap, TypeInfo},
 };
 
@@ -53,75 +57,365 @@ impl DerefMut for DynComponent {
     }
 }
 
+/// Type-erased information about a component type, including how to build its lifecycle events.
+#[derive(Clone, Copy, Debug)]
+pub struct ComponentInfo {
+    pub type_id: TypeInfo,
+    pub(crate) on_add: LifecycleEvent,
+    pub(crate) on_insert: LifecycleEvent,
+    pub(crate) on_remove: LifecycleEvent,
+}
+
+impl ComponentInfo {
+    pub fn of<T: Component>() -> Self {
+        Self {
+            type_id: TypeInfo::of::<T>(),
+            on_add: LifecycleEvent::of::<ComponentAdded<T>>(),
+            on_insert: LifecycleEvent::of::<ComponentInserted<T>>(),
+            on_remove: LifecycleEvent::of::<ComponentRemoved<T>>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LifecycleEvent {
+    pub(crate) type_id: TypeInfo,
+    pub(crate) new: fn(Entity) -> Arc<dyn Component>,
+}
+
+impl LifecycleEvent {
+    fn of<E: ComponentLifecycle>() -> Self {
+        Self {
+            type_id: TypeInfo::of::<E>(),
+            new: |entity| Arc::new(E::new(entity)),
+        }
+    }
+}
+
+trait ComponentLifecycle: Component {
+    fn new(entity: Entity) -> Self;
+}
+
+macro_rules! define_lifecycle_events {
+    ($($(#[$attr:meta])* $name:ident),*) => {
+        $(
+            $(#[$attr])*
+            pub struct $name<T: Component> {
+                pub entity: Entity,
+                _marker: PhantomData<T>,
+            }
+
+            impl<T: Component> ComponentLifecycle for $name<T> {
+                fn new(entity: Entity) -> Self {
+                    Self {
+                        entity,
+                        _marker: PhantomData,
+                    }
+                }
+            }
+
+            impl<T: Component> Clone for $name<T> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<T: Component> Copy for $name<T> {}
+
+            impl<T: Component> Debug for $name<T> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct(stringify!($name))
+                        .field("component", &std::any::type_name::<T>())
+                        .field("entity", &self.entity)
+                        .finish()
+                }
+            }
+        )*
+    };
+}
+
+define_lifecycle_events!(
+    /// Fired when an entity gains a `T` it didn't have before.
+    ComponentAdded,
+    /// Fired every time a `T` is inserted on an entity, including when it replaces an existing one.
+    ComponentInserted,
+    /// Fired after a `T` was removed from an entity, or the entity was despawned.
+    ComponentRemoved
+);
+
+/// Where a single component lives.
+#[derive(Clone)]
+pub(crate) enum ComponentLoan {
+    Single(Arc<RwLock<Option<DynComponent>>>, Arc<ComponentTicks>),
+    Column(Arc<RwLock<Column>>),
+}
+
+impl ComponentLoan {
+    pub(crate) async fn read<T: Component>(self, entity: Entity, last_run: u64) -> Option<Ref<T>> {
+        match self {
+            ComponentLoan::Single(loan, ticks) => {
+                let info = LockInfo::component(Arc::as_ptr(&loan), type_name::<T>(), Some(entity));
+                let (inner, held) = track_lock(info, Access::Read, loan.read_owned()).await;
+                // the component may have been taken out while we were waiting for the lock
+                inner.is_some().then(|| {
+                    Ref::<T>::new(RefInner::Single(inner, ticks), last_run)
+                        .with_held(held.map(Arc::new))
+                })
+            }
+            ComponentLoan::Column(column) => {
+                let info =
+                    LockInfo::component(Arc::as_ptr(&column), type_name::<T>(), Some(entity));
+                let (column, held) = track_lock(info, Access::Read, column.read_owned()).await;
+                let index = column.index_of(entity)?;
+                Some(
+                    Ref::<T>::new(RefInner::Column(Arc::new(column), index), last_run)
+                        .with_held(held.map(Arc::new)),
+                )
+            }
+        }
+    }
+
+    pub(crate) async fn write<T: Component>(
+        self,
+        entity: Entity,
+        change_tick: ChangeTick,
+        last_run: u64,
+    ) -> Option<Mut<T>> {
+        match self {
+            ComponentLoan::Single(loan, ticks) => {
+                let info = LockInfo::component(Arc::as_ptr(&loan), type_name::<T>(), Some(entity));
+                let (inner, held) = track_lock(info, Access::Write, loan.write_owned()).await;
+                inner.is_some().then(|| {
+                    Mut::<T>::new(MutInner::Single(inner, ticks), change_tick, last_run)
+                        .with_held(held)
+                })
+            }
+            ComponentLoan::Column(column) => {
+                let info =
+                    LockInfo::component(Arc::as_ptr(&column), type_name::<T>(), Some(entity));
+                let (column, held) = track_lock(info, Access::Write, column.write_owned()).await;
+                let index = column.index_of(entity)?;
+                Some(
+                    Mut::<T>::new(MutInner::Column(column, index), change_tick, last_run)
+                        .with_held(held),
+                )
+            }
+        }
+    }
+
+    pub(crate) async fn ticks(&self, entity: Entity) -> Option<ComponentTicks> {
+        match self {
+            ComponentLoan::Single(_, ticks) => Some((**ticks).clone()),
+            ComponentLoan::Column(column) => {
+                let column = column.read().await;
+                let index = column.index_of(entity)?;
+                Some(column.ticks_at(index).clone())
+            }
+        }
+    }
+}
+
 pub struct ComponentStorage {
-    type_id: TypeInfo,
-    loan: Arc<RwLock<Option<DynComponent>>>,
+    info: ComponentInfo,
+    loan: ComponentLoan,
 }
 
 impl ComponentStorage {
-    pub fn new<T: Component>(component: T) -> Self {
+    pub fn new<T: Component>(component: T, tick: u64) -> Self {
+        Self::new_dyn(
+            ComponentInfo::of::<T>(),
+            Box::new(component),
+            ComponentTicks::new(tick),
+        )
+    }
+
+    pub(crate) fn new_dyn(
+        info: ComponentInfo,
+        component: Box<dyn Component>,
+        ticks: ComponentTicks,
+    ) -> Self {
         ComponentStorage {
-            type_id: TypeInfo::of::<T>(),
-            loan: Arc::new(RwLock::new(Some(DynComponent::new(component)))),
+            info,
+            loan: ComponentLoan::Single(
+                Arc::new(RwLock::new(Some(DynComponent {
+                    type_id: info.type_id,
+                    component,
+                }))),
+                Arc::new(ticks),
+            ),
         }
     }
 
+    pub fn info(&self) -> ComponentInfo {
+        self.info
+    }
+
     pub fn is<T: Component>(&self) -> bool {
-        self.type_id == TypeInfo::of::<T>()
+        self.info.type_id == TypeInfo::of::<T>()
+    }
+
+    pub fn storage_type(&self) -> StorageType {
+        match self.loan {
+            ComponentLoan::Single(..) => StorageType::PerEntity,
+            ComponentLoan::Column(_) => StorageType::Column,
+        }
     }
 }
 
+pub(crate) enum RefInner {
+    Single(Read<Option<DynComponent>>, Arc<ComponentTicks>),
+    Column(Arc<Read<Column>>, usize),
+}
+
 pub struct Ref<T: Component> {
-    pub(crate) inner: Read<Option<DynComponent>>,
+    pub(crate) inner: RefInner,
+    pub(crate) last_run: u64,
+    pub(crate) held: Option<Arc<HeldLock>>,
     pub(crate) _marker: PhantomData<T>,
 }
 
+impl<T: Component> Ref<T> {
+    pub(crate) fn new(inner: RefInner, last_run: u64) -> Self {
+        Self {
+            inner,
+            last_run,
+            held: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn with_held(mut self, held: Option<Arc<HeldLock>>) -> Self {
+        self.held = held;
+        self
+    }
+
+    pub fn ticks(&self) -> &ComponentTicks {
+        match &self.inner {
+            RefInner::Single(_, ticks) => ticks,
+            RefInner::Column(column, index) => column.ticks_at(*index),
+        }
+    }
+
+    /// Returns `true` if the value was added since the handler that fetched it last ran.
+    pub fn is_added(&self) -> bool {
+        self.ticks().is_added(self.last_run)
+    }
+
+    /// Returns `true` if the value was added or mutably dereferenced since the handler that fetched it last ran.
+    pub fn is_changed(&self) -> bool {
+        self.ticks().is_changed(self.last_run)
+    }
+
+    pub fn last_changed(&self) -> u64 {
+        self.ticks().changed()
+    }
+}
+
 impl<T: Component> Deref for Ref<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.inner.as_ref().unwrap().downcast_ref().unwrap()
+        match &self.inner {
+            RefInner::Single(inner, _) => inner.as_ref().unwrap().downcast_ref().unwrap(),
+            RefInner::Column(column, index) => column.get_at(*index),
+        }
     }
 }
 
 impl<T: Component + Debug> Debug for Ref<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner
-            .as_ref()
-            .unwrap()
-            .downcast_ref::<T>()
-            .unwrap()
-            .fmt(f)
+        (**self).fmt(f)
     }
 }
 
+pub(crate) enum MutInner {
+    Single(Write<Option<DynComponent>>, Arc<ComponentTicks>),
+    Column(Write<Column>, usize),
+    /// One component of a column that a query borrowed as a whole.
+    Lent(Arc<ColumnWrite>, usize),
+}
+
 pub struct Mut<T: Component> {
-    pub(crate) inner: Write<Option<DynComponent>>,
+    pub(crate) inner: MutInner,
+    pub(crate) change_tick: ChangeTick,
+    pub(crate) last_run: u64,
+    pub(crate) held: Option<HeldLock>,
     pub(crate) _marker: PhantomData<T>,
 }
 
+impl<T: Component> Mut<T> {
+    pub(crate) fn new(inner: MutInner, change_tick: ChangeTick, last_run: u64) -> Self {
+        Self {
+            inner,
+            change_tick,
+            last_run,
+            held: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn with_held(mut self, held: Option<HeldLock>) -> Self {
+        self.held = held;
+        self
+    }
+
+    pub fn ticks(&self) -> &ComponentTicks {
+        match &self.inner {
+            MutInner::Single(_, ticks) => ticks,
+            MutInner::Column(column, index) => column.ticks_at(*index),
+            MutInner::Lent(column, index) => column.ticks_at(*index),
+        }
+    }
+
+    pub fn is_added(&self) -> bool {
+        self.ticks().is_added(self.last_run)
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.ticks().is_changed(self.last_run)
+    }
+
+    pub fn last_changed(&self) -> u64 {
+        self.ticks().changed()
+    }
+}
+
 impl<T: Component> Deref for Mut<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.inner.as_ref().unwrap().downcast_ref().unwrap()
+        match &self.inner {
+            MutInner::Single(inner, _) => inner.as_ref().unwrap().downcast_ref().unwrap(),
+            MutInner::Column(column, index) => column.get_at(*index),
+            // SAFETY: this `Mut` owns the loan for `index`
+            MutInner::Lent(column, index) => unsafe { column.get_at::<T>(*index).as_ref() },
+        }
     }
 }
 
 impl<T: Component> DerefMut for Mut<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.inner.as_mut().unwrap().downcast_mut().unwrap()
+        match &mut self.inner {
+            MutInner::Single(inner, ticks) => {
+                self.change_tick.record_change(ticks);
+                inner.as_mut().unwrap().downcast_mut().unwrap()
+            }
+            MutInner::Column(column, index) => {
+                self.change_tick.record_change(column.ticks_at(*index));
+                column.get_at_mut(*index)
+            }
+            MutInner::Lent(column, index) => {
+                self.change_tick.record_change(column.ticks_at(*index));
+                // SAFETY: this `Mut` owns the loan for `index`, and is borrowed mutably
+                unsafe { column.get_at::<T>(*index).as_mut() }
+            }
+        }
     }
 }
 
 impl<T: Component + Debug> Debug for Mut<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner
-            .as_ref()
-            .unwrap()
-            .downcast_ref::<T>()
-            .unwrap()
-            .fmt(f)
+        (**self).fmt(f)
     }
 }
 
@@ -129,59 +423,139 @@ impl<T: Component + Debug> Debug for Mut<T> {
 pub struct Components {
     entity_map: EntityMap<TypeIdMap<ComponentStorage>>,
     component_map: TypeIdMap<EntitySet>,
+    columns: TypeIdMap<Arc<RwLock<Column>>>,
+    change_tick: ChangeTick,
 }
 
 impl Components {
-    pub async fn insert<T: Component>(&mut self, entity: Entity, component: T) -> Option<T> {
+    pub fn new(change_tick: ChangeTick) -> Self {
+        Self {
+            change_tick,
+            ..Default::default()
+        }
+    }
+
+    pub fn change_tick(&self) -> &ChangeTick {
+        &self.change_tick
+    }
+
+    pub fn storage_type<T: Component>(&self) -> StorageType {
+        if self.columns.contains_type::<T>() {
+            StorageType::Column
+        } else {
+            StorageType::PerEntity
+        }
+    }
+
+    /// Changes how components of type `T` are stored, moving any existing ones over.
+    pub async fn set_storage_type<T: Component>(&mut self, storage_type: StorageType) {
+        if self.storage_type::<T>() == storage_type {
+            return;
+        }
+
         let component_type_id = TypeInfo::of::<T>();
 
-        self.component_map
-            .entry(component_type_id)
-            .or_default()
-            .insert(entity);
+        match storage_type {
+            StorageType::Column => {
+                let column = Arc::new(RwLock::new(Column::new::<T>()));
+                let mut column_lock = column.write().await;
+
+                for entity in self
+                    .component_map
+                    .get(&component_type_id)
+                    .into_iter()
+                    .flatten()
+                {
+                    let storage = self
+                        .entity_map
+                        .get_mut(entity)
+                        .and_then(|components| components.get_mut(&component_type_id))
+                        .unwrap();
+
+                    let loan =
+                        std::mem::replace(&mut storage.loan, ComponentLoan::Column(column.clone()));
+                    let ComponentLoan::Single(loan, ticks) = loan else {
+                        unreachable!()
+                    };
+                    let component = loan.write().await.take().unwrap();
+                    column_lock.insert_dyn(*entity, component.component, (*ticks).clone());
+                }
+
+                drop(column_lock);
+                self.columns.insert(component_type_id, column);
+            }
+            StorageType::PerEntity => {
+                let column = self.columns.remove(&component_type_id).unwrap();
+                let mut column = column.write().await;
+
+                for (entity, component, ticks) in column.drain_dyn() {
+                    let storage = self
+                        .entity_map
+                        .get_mut(&entity)
+                        .and_then(|components| components.get_mut(&component_type_id))
+                        .unwrap();
+
+                    *storage = ComponentStorage::new_dyn(storage.info, component, ticks);
+                }
+            }
+        }
+    }
 
+    pub async fn insert<T: Component>(&mut self, entity: Entity, component: T) -> Option<T> {
         let old = self
-            .entity_map
-            .entry(entity)
-            .or_default()
-            .insert(component_type_id, ComponentStorage::new(component))?;
-
-        let old = old.loan.write().await.take().unwrap();
-        let old: T = *old.component.downcast().unwrap_or_else(|_| unreachable!());
+            .insert_dyn(entity, ComponentInfo::of::<T>(), Box::new(component))
+            .await?;
+        let old: T = *old.downcast().unwrap_or_else(|_| unreachable!());
         Some(old)
     }
 
-    pub fn insert_discard<T: Component>(&mut self, entity: Entity, component: T) {
-        let component_type_id = TypeInfo::of::<T>();
+    pub async fn insert_discard<T: Component>(&mut self, entity: Entity, component: T) {
+        self.insert_dyn(entity, ComponentInfo::of::<T>(), Box::new(component))
+            .await;
+    }
 
-        self.entity_map
-            .entry(entity)
-            .or_default()
-            .insert(component_type_id, ComponentStorage::new(component));
+    pub async fn insert_bundle<T: Bundle>(&mut self, entity: Entity, bundle: T) {
+        for (info, component) in bundle.into_dyn_components() {
+            self.insert_dyn(entity, info, component).await;
+        }
+    }
+
+    /// Inserts a type-erased component, returning the one it replaced, if any.
+    pub(crate) async fn insert_dyn(
+        &mut self,
+        entity: Entity,
+        info: ComponentInfo,
+        component: Box<dyn Component>,
+    ) -> Option<Box<dyn Component>> {
+        let component_type_id = info.type_id;
+        let ticks = ComponentTicks::new(self.change_tick.increment());
 
         self.component_map
             .entry(component_type_id)
             .or_default()
             .insert(entity);
-    }
 
-    pub fn insert_bundle<T: Bundle>(&mut self, entity: Entity, bundle: T) {
-        for (component_type_id, component) in bundle.into_dyn_components() {
-            self.entity_map.entry(entity).or_default().insert(
+        let components = self.entity_map.entry(entity).or_default();
+
+        if let Some(column) = self.columns.get(&component_type_id) {
+            components.insert(
                 component_type_id,
                 ComponentStorage {
-                    loan: Arc::new(RwLock::new(Some(DynComponent {
-                        type_id: component_type_id,
-                        component,
-                    }))),
-                    type_id: component_type_id,
+                    info,
+                    loan: ComponentLoan::Column(column.clone()),
                 },
             );
-
-            self.component_map
-                .entry(component_type_id)
-                .or_default()
-                .insert(entity);
+            column.write().await.insert_dyn(entity, component, ticks)
+        } else {
+            let old = components.insert(
+                component_type_id,
+                ComponentStorage::new_dyn(info, component, ticks),
+            )?;
+            let ComponentLoan::Single(old, _) = old.loan else {
+                unreachable!()
+            };
+            let old = old.write().await.take().unwrap();
+            Some(old.component)
         }
     }
 
@@ -195,34 +569,56 @@ impl Components {
             .unwrap()
             .remove(&entity);
 
-        let component = component.loan.write().await.take().unwrap();
-        let component = *component
-            .component
-            .downcast::<T>()
-            .unwrap_or_else(|_| unreachable!());
+        let component = Self::take(component, entity).await?;
+        let component = *component.downcast::<T>().unwrap_or_else(|_| unreachable!());
         Some(component)
     }
 
-    pub async fn get<T: Component>(&self, entity: Entity) -> Option<Ref<T>> {
+    async fn take(storage: ComponentStorage, entity: Entity) -> Option<Box<dyn Component>> {
+        match storage.loan {
+            ComponentLoan::Single(loan, _) => Some(loan.write().await.take()?.component),
+            ComponentLoan::Column(column) => column.write().await.remove_dyn(entity),
+        }
+    }
+
+    /// Removes every component of the entity, returning what was removed.
+    pub async fn despawn(&mut self, entity: Entity) -> Option<Vec<ComponentInfo>> {
+        let components = self.entity_map.remove(&entity)?;
+
+        let mut removed = Vec::with_capacity(components.len());
+        for (component_type_id, storage) in components {
+            if let Some(entities) = self.component_map.get_mut(&component_type_id) {
+                entities.remove(&entity);
+            }
+
+            removed.push(storage.info);
+            if storage.storage_type() == StorageType::Column {
+                Self::take(storage, entity).await;
+            }
+        }
+
+        Some(removed)
+    }
+
+    pub(crate) fn loan<T: Component>(&self, entity: Entity) -> Option<ComponentLoan> {
         let component_type_id = TypeInfo::of::<T>();
         let components = self.entity_map.get(&entity)?;
         let component = components.get(&component_type_id)?;
-        let inner = component.loan.clone().read_owned().await;
-        Some(Ref {
-            inner,
-            _marker: PhantomData,
-        })
+        Some(component.loan.clone())
+    }
+
+    pub(crate) fn column<T: Component>(&self) -> Option<Arc<RwLock<Column>>> {
+        self.columns.get_for::<T>().cloned()
+    }
+
+    pub async fn get<T: Component>(&self, entity: Entity) -> Option<Ref<T>> {
+        self.loan::<T>(entity)?.read(entity, 0).await
     }
 
     pub async fn get_mut<T: Component>(&self, entity: Entity) -> Option<Mut<T>> {
-        let component_type_id = TypeInfo::of::<T>();
-        let components = self.entity_map.get(&entity)?;
-        let component = components.get(&component_type_id)?;
-        let inner = component.loan.clone().write_owned().await;
-        Some(Mut {
-            inner,
-            _marker: PhantomData,
-        })
+        self.loan::<T>(entity)?
+            .write(entity, self.change_tick.clone(), 0)
+            .await
     }
 
     pub fn has<T: Component>(&self, entity: Entity) -> bool {
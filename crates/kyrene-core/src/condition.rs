@@ -0,0 +1,106 @@
+use std::{future::Future, marker::PhantomData, sync::Arc};
+
+use futures::future::BoxFuture;
+
+use crate::{
+    handler::{HandlerParam, HandlerParamItem, HandlerParamState},
+    lock::RwLock,
+    util::SyncBoxFuture,
+    world_handle::WorldHandle,
+};
+
+/// An async fn deciding whether a handler or handler set should run, taking any [`HandlerParam`]s.
+///
+/// If any of its params can't be fetched (e.g. a missing resource), it counts as returning `false`.
+pub trait ConditionFn<M>: Send + Sync + 'static {
+    type Param: HandlerParam + 'static;
+
+    fn run(&self, param: HandlerParamItem<Self::Param>) -> SyncBoxFuture<'static, bool>;
+}
+
+impl<Func, Fut> ConditionFn<fn() -> Fut> for Func
+where
+    Func: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = bool> + Send + Sync + 'static,
+{
+    type Param = ();
+
+    fn run(&self, _param: HandlerParamItem<Self::Param>) -> SyncBoxFuture<'static, bool> {
+        Box::pin((self)())
+    }
+}
+
+macro_rules! impl_condition_fn {
+    ($($param:ident),*) => {
+        #[allow(unused, non_snake_case)]
+        impl<Func, Fut, $($param),*> ConditionFn<fn($($param,)*) -> Fut> for Func
+        where
+            Func: Fn($($param),*) -> Fut + Send + Sync + 'static
+                + Fn($(HandlerParamItem<$param>),*) -> Fut + Send + Sync + 'static,
+            $($param: HandlerParam + 'static),*,
+            Fut: Future<Output = bool> + Send + Sync + 'static,
+        {
+            type Param = ($($param),*);
+
+            fn run(&self, param: HandlerParamItem<Self::Param>) -> SyncBoxFuture<'static, bool> {
+                let ($($param),*) = param;
+                Box::pin((self)($($param),*))
+            }
+        }
+    };
+}
+
+impl_condition_fn!(A);
+impl_condition_fn!(A, B);
+impl_condition_fn!(A, B, C);
+impl_condition_fn!(A, B, C, D);
+impl_condition_fn!(A, B, C, D, E);
+impl_condition_fn!(A, B, C, D, E, F);
+impl_condition_fn!(A, B, C, D, E, F, G);
+impl_condition_fn!(A, B, C, D, E, F, G, H);
+
+pub(crate) trait Condition: Send + Sync {
+    fn evaluate(&self, world: WorldHandle) -> BoxFuture<'static, bool>;
+}
+
+struct FunctionCondition<M, F>
+where
+    F: ConditionFn<M>,
+{
+    func: Arc<F>,
+    state: Arc<RwLock<Option<HandlerParamState<F::Param>>>>,
+    _marker: PhantomData<fn() -> M>,
+}
+
+impl<M, F> Condition for FunctionCondition<M, F>
+where
+    F: ConditionFn<M>,
+{
+    fn evaluate(&self, world: WorldHandle) -> BoxFuture<'static, bool> {
+        let func = self.func.clone();
+        let state = self.state.clone();
+        Box::pin(async move {
+            let mut state_lock = state.write().await;
+            if state_lock.is_none() {
+                state_lock.replace(<F::Param>::init_state(world.clone()).await);
+            }
+            let state = state_lock.as_mut().unwrap();
+            if !<F::Param>::can_run(world.clone(), state).await {
+                return false;
+            }
+            let Some(param) = <F::Param>::fetch(world, state).await else {
+                return false;
+            };
+            drop(state_lock);
+            func.run(param).await
+        })
+    }
+}
+
+pub(crate) fn into_condition<M: 'static, F: ConditionFn<M>>(func: F) -> Arc<dyn Condition> {
+    Arc::new(FunctionCondition {
+        func: Arc::new(func),
+        state: Arc::new(RwLock::new(None)),
+        _marker: PhantomData,
+    })
+}
@@ -0,0 +1,263 @@
+use std::{
+    fmt::{Display, Write as _},
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        LazyLock,
+    },
+};
+
+use crate::{
+    entity::Entity,
+    util::{FxHashMap, FxHashSet},
+};
+
+static LOCK_DIAGNOSTICS: AtomicBool = AtomicBool::new(false);
+
+static NEXT_HANDLER_RUN: AtomicU64 = AtomicU64::new(0);
+
+static LOCK_GRAPH: LazyLock<std::sync::Mutex<LockGraph>> = LazyLock::new(Default::default);
+
+tokio::task_local! {
+    static CURRENT_HANDLER: HandlerRun;
+}
+
+/// Turns on tracking of which handlers hold and wait on which component and resource locks.
+///
+/// Whenever a handler starts waiting on a lock in a way that closes a wait-for cycle, the handlers,
+/// components, resources and entities involved are logged as an error.
+///
+/// This adds bookkeeping to every [`Ref`](crate::component::Ref) and [`Mut`](crate::component::Mut) taken from inside a handler,
+/// so leave it off unless you're chasing a hang.
+pub fn set_lock_diagnostics(enabled: bool) {
+    LOCK_DIAGNOSTICS.store(enabled, Ordering::Release);
+}
+
+pub fn lock_diagnostics_enabled() -> bool {
+    LOCK_DIAGNOSTICS.load(Ordering::Acquire)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct HandlerRun {
+    id: u64,
+    name: &'static str,
+}
+
+/// Runs a handler, attributing any locks it takes to it.
+pub(crate) async fn handler_scope<F: Future>(name: &'static str, fut: F) -> F::Output {
+    if !lock_diagnostics_enabled() {
+        return fut.await;
+    }
+
+    let run = HandlerRun {
+        id: NEXT_HANDLER_RUN.fetch_add(1, Ordering::Relaxed),
+        name,
+    };
+    CURRENT_HANDLER.scope(run, fut).await
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LockKind {
+    Component,
+    Resource,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    fn conflicts_with(self, other: Access) -> bool {
+        self == Access::Write || other == Access::Write
+    }
+}
+
+/// What a tracked lock guards.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LockInfo {
+    /// The address of the lock itself, which is what actually identifies it.
+    id: usize,
+    kind: LockKind,
+    type_name: &'static str,
+    entity: Option<Entity>,
+}
+
+impl LockInfo {
+    /// `entity` is `None` when a whole column is borrowed at once.
+    pub(crate) fn component<T: ?Sized>(
+        lock: *const T,
+        type_name: &'static str,
+        entity: Option<Entity>,
+    ) -> Self {
+        Self {
+            id: lock as *const () as usize,
+            kind: LockKind::Component,
+            type_name,
+            entity,
+        }
+    }
+
+    pub(crate) fn resource<T: ?Sized>(lock: *const T, type_name: &'static str) -> Self {
+        Self {
+            id: lock as *const () as usize,
+            kind: LockKind::Resource,
+            type_name,
+            entity: None,
+        }
+    }
+}
+
+impl Display for LockInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.kind, self.entity) {
+            (LockKind::Component, Some(entity)) => {
+                write!(f, "component `{}` of {:?}", self.type_name, entity)
+            }
+            (LockKind::Component, None) => write!(f, "every component `{}`", self.type_name),
+            (LockKind::Resource, _) => write!(f, "resource `{}`", self.type_name),
+        }
+    }
+}
+
+/// Keeps a tracked lock registered as held by a handler until dropped.
+#[derive(Debug)]
+pub(crate) struct HeldLock {
+    run: u64,
+    lock: usize,
+}
+
+impl Drop for HeldLock {
+    fn drop(&mut self) {
+        LOCK_GRAPH.lock().unwrap().release(self.run, self.lock);
+    }
+}
+
+/// Awaits `acquire`, recording the current handler as waiting on the lock until it gets it, and as holding it after.
+///
+/// Does nothing extra if diagnostics are disabled or this isn't running inside a handler.
+pub(crate) async fn track_lock<G>(
+    info: LockInfo,
+    access: Access,
+    acquire: impl Future<Output = G>,
+) -> (G, Option<HeldLock>) {
+    let run = match CURRENT_HANDLER.try_with(|run| *run) {
+        Ok(run) if lock_diagnostics_enabled() => run,
+        _ => return (acquire.await, None),
+    };
+
+    {
+        let mut graph = LOCK_GRAPH.lock().unwrap();
+        graph.waiting.insert(run.id, (run, info, access));
+        if let Some(cycle) = graph.find_cycle(run.id) {
+            tracing::error!("{}", LockGraph::report(&cycle));
+        }
+    }
+
+    // unregisters the wait even if the acquiring future is dropped
+    let waiting = Waiting(run.id);
+    let guard = acquire.await;
+    drop(waiting);
+
+    let mut graph = LOCK_GRAPH.lock().unwrap();
+    graph
+        .held
+        .entry(info.id)
+        .or_insert_with(|| (info, Vec::new()))
+        .1
+        .push((run, access));
+
+    (
+        guard,
+        Some(HeldLock {
+            run: run.id,
+            lock: info.id,
+        }),
+    )
+}
+
+struct Waiting(u64);
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        LOCK_GRAPH.lock().unwrap().waiting.remove(&self.0);
+    }
+}
+
+#[derive(Default)]
+struct LockGraph {
+    held: FxHashMap<usize, (LockInfo, Vec<(HandlerRun, Access)>)>,
+    waiting: FxHashMap<u64, (HandlerRun, LockInfo, Access)>,
+}
+
+impl LockGraph {
+    fn release(&mut self, run: u64, lock: usize) {
+        let Some((_, holders)) = self.held.get_mut(&lock) else {
+            return;
+        };
+        if let Some(index) = holders.iter().position(|(holder, _)| holder.id == run) {
+            holders.swap_remove(index);
+        }
+        if holders.is_empty() {
+            self.held.remove(&lock);
+        }
+    }
+
+    /// Follows the wait-for edges out of `start`, returning the waits that lead back to it, if any.
+    fn find_cycle(&self, start: u64) -> Option<Vec<(HandlerRun, LockInfo, Access, HandlerRun)>> {
+        let mut path = Vec::new();
+        let mut visited = FxHashSet::default();
+        self.find_cycle_from(start, start, &mut path, &mut visited)
+            .then_some(path)
+    }
+
+    fn find_cycle_from(
+        &self,
+        start: u64,
+        current: u64,
+        path: &mut Vec<(HandlerRun, LockInfo, Access, HandlerRun)>,
+        visited: &mut FxHashSet<u64>,
+    ) -> bool {
+        let Some(&(waiter, info, access)) = self.waiting.get(&current) else {
+            return false;
+        };
+        let Some((_, holders)) = self.held.get(&info.id) else {
+            return false;
+        };
+
+        for &(holder, held_access) in holders {
+            if !access.conflicts_with(held_access) {
+                continue;
+            }
+
+            path.push((waiter, info, access, holder));
+            if holder.id == start
+                || (visited.insert(holder.id)
+                    && self.find_cycle_from(start, holder.id, path, visited))
+            {
+                return true;
+            }
+            path.pop();
+        }
+
+        false
+    }
+
+    fn report(cycle: &[(HandlerRun, LockInfo, Access, HandlerRun)]) -> String {
+        let mut report = format!("Deadlock detected between {} handler(s):", cycle.len());
+        for (waiter, info, access, holder) in cycle {
+            let verb = match access {
+                Access::Read => "read",
+                Access::Write => "write",
+            };
+            write!(
+                report,
+                "\n    `{}` is waiting to {} {}, held by `{}`",
+                waiter.name, verb, info, holder.name
+            )
+            .unwrap();
+        }
+        report
+    }
+}
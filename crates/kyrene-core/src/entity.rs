@@ -177,6 +177,12 @@ impl Entities {
         *self.free_cursor.get_mut() = new_free_cursor;
     }
 
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.generations
+            .get(entity.id as usize)
+            .is_some_and(|generation| *generation == entity.generation)
+    }
+
     pub fn find_by_id(&self, id: u32) -> Option<Entity> {
         let id = id as usize;
         if let Some(generation) = self.generations.get(id) {
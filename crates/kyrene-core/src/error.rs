@@ -0,0 +1,171 @@
+use std::{
+    any::Any,
+    error::Error,
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use crate::{
+    component::Component, handler::DynEventHandler, request::Responses, util::SyncBoxFuture,
+    world_handle::WorldHandle,
+};
+
+/// An error returned by a fallible event handler, or the payload of one that panicked.
+///
+/// Anything that converts into a boxed [`Error`] converts into this, so `?` works on most errors in a handler.
+pub struct HandlerError {
+    inner: Box<dyn Error + Send + Sync>,
+}
+
+impl HandlerError {
+    pub fn new(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self {
+            inner: error.into(),
+        }
+    }
+
+    pub(crate) fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "Box<dyn Any>".to_string(),
+            },
+        };
+        Self::new(format!("panicked: {message}"))
+    }
+
+    pub fn inner(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.inner
+    }
+
+    pub fn into_inner(self) -> Box<dyn Error + Send + Sync> {
+        self.inner
+    }
+}
+
+impl<E: Into<Box<dyn Error + Send + Sync>>> From<E> for HandlerError {
+    fn from(error: E) -> Self {
+        Self::new(error)
+    }
+}
+
+impl Debug for HandlerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl Display for HandlerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
+pub type HandlerResult = Result<(), HandlerError>;
+
+/// Implemented for the return types a handler of event `T` can have.
+///
+/// Only handlers of a [`Request`](crate::request::Request) can return its
+/// [`Response`](crate::request::Response).
+pub trait IntoHandlerResult<T: Component>: Send + Sync + 'static {
+    fn into_handler_result(self) -> HandlerResult;
+
+    /// Like [`IntoHandlerResult::into_handler_result`], but hands any [`Response`](crate::request::Response)
+    /// to the [`WorldHandle::request`] that fired the event, if there is one.
+    fn into_handler_result_with(self, responses: Option<&Responses>) -> HandlerResult
+    where
+        Self: Sized,
+    {
+        let _ = responses;
+        self.into_handler_result()
+    }
+}
+
+impl<T: Component> IntoHandlerResult<T> for () {
+    fn into_handler_result(self) -> HandlerResult {
+        Ok(())
+    }
+}
+
+impl<T, E> IntoHandlerResult<T> for Result<(), E>
+where
+    T: Component,
+    E: Into<HandlerError> + Send + Sync + 'static,
+{
+    fn into_handler_result(self) -> HandlerResult {
+        self.map_err(Into::into)
+    }
+}
+
+/// What to do when a handler returns an error or panics. Insert it as a resource to change it.
+///
+/// Defaults to [`HandlerErrorPolicy::Log`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HandlerErrorPolicy {
+    /// Log the error and carry on.
+    #[default]
+    Log,
+    /// Panic with the error, taking down whatever fired the event.
+    Panic,
+    /// Log the error and fire a [`HandlerFailed`] event.
+    Fire,
+    /// Log the error and stop running the handler.
+    Disable,
+}
+
+/// Fired when a handler fails under [`HandlerErrorPolicy::Fire`].
+#[derive(Clone, Debug)]
+pub struct HandlerFailed {
+    pub handler: &'static str,
+    pub event_type: &'static str,
+    pub error: Arc<HandlerError>,
+}
+
+impl WorldHandle {
+    /// Applies the [`HandlerErrorPolicy`] to a failed handler.
+    // boxed, since firing `HandlerFailed` can end up back here
+    pub(crate) fn report_handler_error<'a>(
+        &'a self,
+        handler: &'a DynEventHandler,
+        event_type: &'static str,
+        error: HandlerError,
+    ) -> SyncBoxFuture<'a, ()> {
+        Box::pin(async move {
+            let policy = match self.get_resource::<HandlerErrorPolicy>().await {
+                Some(policy) => *policy,
+                None => HandlerErrorPolicy::default(),
+            };
+
+            if policy == HandlerErrorPolicy::Panic {
+                panic!(
+                    "Handler `{}` for `{}` failed: {}",
+                    handler.name, event_type, error
+                );
+            }
+
+            tracing::error!(
+                "Handler `{}` for `{}` failed: {}",
+                handler.name,
+                event_type,
+                error
+            );
+
+            match policy {
+                // a failing `HandlerFailed` handler would otherwise keep firing itself
+                HandlerErrorPolicy::Fire
+                    if event_type != std::any::type_name::<HandlerFailed>() =>
+                {
+                    let failed = HandlerFailed {
+                        handler: handler.name,
+                        event_type,
+                        error: Arc::new(error),
+                    };
+                    self.fire_event(failed, false).await;
+                }
+                HandlerErrorPolicy::Disable => handler.set_enabled(false),
+                _ => {}
+            }
+        })
+    }
+}
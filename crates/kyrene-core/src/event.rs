@@ -2,29 +2,97 @@ use std::{
     collections::VecDeque,
     marker::PhantomData,
     ops::Deref,
-    sync::Arc,
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
+use futures::FutureExt;
 use petgraph::prelude::*;
 use tokio::task::JoinSet;
 
 use crate::{
-    handler::{DynEventHandlers, IntoHandlerConfig},
+    commands::{self, CommandQueue},
+    condition::Condition,
+    diagnostics,
+    entity::Entity,
+    error::HandlerError,
+    handler::{DynEventHandler, DynEventHandlers, EventHandlerMeta, HandlerId, IntoHandlerConfig},
+    handler_set::{HandlerSetConfig, InternedHandlerSet, IntoHandlerSetConfig},
     lock::Mutex,
     prelude::{Component, WorldHandle},
-    util::{FxHashMap, TypeInfo},
+    request::Responses,
+    util::{FxHashMap, SyncFuture, TypeInfo},
 };
 
 pub struct EventInner<T: Component> {
     event: Arc<T>,
     delta_time: Option<Duration>,
+    propagation: Option<Propagation>,
+    responses: Option<Arc<Responses>>,
 }
 
 impl<T: Component> EventInner<T> {
     pub fn delta_time(&self) -> Option<Duration> {
         self.delta_time
     }
+
+    /// The entity the event was fired at, if it was fired with [`WorldHandle::fire_entity_event`].
+    pub fn target(&self) -> Option<Entity> {
+        Some(self.propagation.as_ref()?.target)
+    }
+
+    /// The entity the event has bubbled up to, which is the one whose handlers are running now.
+    pub fn current_target(&self) -> Option<Entity> {
+        Some(self.propagation.as_ref()?.current_target)
+    }
+
+    /// Keeps an entity-targeted event from bubbling up to the current target's parent.
+    ///
+    /// The other handlers for the current target still run. Does nothing for events that weren't fired at an entity.
+    pub fn stop_propagation(&self) {
+        if let Some(propagation) = &self.propagation {
+            propagation.stopped.store(true, Ordering::Release);
+        }
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation
+            .as_ref()
+            .is_some_and(|propagation| propagation.stopped.load(Ordering::Acquire))
+    }
+}
+
+/// Extra state that travels with a single fire of an event.
+#[derive(Clone, Default)]
+pub(crate) struct EventContext {
+    pub(crate) propagation: Option<Propagation>,
+    pub(crate) responses: Option<Arc<Responses>>,
+}
+
+impl EventContext {
+    /// Whether a handler observing `observed`, or every entity if `None`, runs at this point of the fire.
+    ///
+    /// Handlers that aren't observing anything only run once, when the event reaches its target.
+    fn runs(&self, observed: Option<Entity>) -> bool {
+        match (&self.propagation, observed) {
+            (None, observed) => observed.is_none(),
+            (Some(propagation), None) => propagation.current_target == propagation.target,
+            (Some(propagation), Some(observed)) => propagation.current_target == observed,
+        }
+    }
+}
+
+/// Where an entity-targeted event is on its way up the hierarchy.
+#[derive(Clone)]
+pub(crate) struct Propagation {
+    pub(crate) target: Entity,
+    pub(crate) current_target: Entity,
+    /// Shared by every step of the trip.
+    pub(crate) stopped: Arc<AtomicBool>,
 }
 
 impl<T: Component> Deref for EventInner<T> {
@@ -46,9 +114,16 @@ impl<T: Component> Event<T> {
                 .downcast_arc()
                 .unwrap_or_else(|_| unreachable!()),
             delta_time: event.delta_time,
+            propagation: event.context.propagation,
+            responses: event.context.responses,
         }))
     }
 
+    /// Where anything the handler returns as a [`Response`](crate::request::Response) goes.
+    pub(crate) fn responses(&self) -> Option<Arc<Responses>> {
+        self.0.responses.clone()
+    }
+
     pub fn event(&self) -> &T {
         &self.0.event
     }
@@ -73,6 +148,7 @@ pub struct DynEvent {
     pub(crate) type_id: TypeInfo,
     pub(crate) event: Arc<dyn Component>,
     pub(crate) delta_time: Option<Duration>,
+    pub(crate) context: EventContext,
 }
 
 pub struct EventDispatcher<T: Component> {
@@ -106,22 +182,41 @@ impl<T: Component> EventDispatcher<T> {
         }
     }
 
-    pub fn add_handler<F, M>(&self, handler: F)
+    pub(crate) fn into_dyn(self) -> DynEventDispatcher {
+        self.event
+    }
+
+    pub fn add_handler<F, M>(&self, handler: F) -> HandlerId
     where
         F: IntoHandlerConfig<M, Event = T>,
         M: 'static,
     {
-        self.event.add_handler(handler);
+        self.event.add_handler(handler)
+    }
+
+    pub fn handler_ids(&self) -> Vec<HandlerId> {
+        self.event.handlers.ids()
+    }
+
+    /// See [`World::configure_set`](crate::world::World::configure_set).
+    pub fn configure_set(&self, config: impl IntoHandlerSetConfig) {
+        self.event.handlers.configure_set(config.into_config());
     }
 
     pub async fn fire(&self, world: WorldHandle, event: T, await_all_handlers: bool) -> usize {
         self.event.fire::<T>(world, event, await_all_handlers).await
     }
+
+    /// See [`WorldHandle::fire_entity_event`].
+    pub async fn fire_at(&self, world: WorldHandle, target: Entity, event: T) -> usize {
+        self.event.fire_at::<T>(world, target, event).await
+    }
 }
 
 pub(crate) struct DynEventDispatcher {
     pub(crate) handlers: DynEventHandlers,
     type_id: TypeInfo,
+    pub(crate) type_name: &'static str,
     last_fired: Arc<Mutex<Option<Instant>>>,
 }
 
@@ -130,6 +225,7 @@ impl Clone for DynEventDispatcher {
         Self {
             handlers: self.handlers.clone(),
             type_id: self.type_id,
+            type_name: self.type_name,
             last_fired: self.last_fired.clone(),
         }
     }
@@ -140,18 +236,19 @@ impl DynEventDispatcher {
         Self {
             handlers: DynEventHandlers::new::<T>(),
             type_id: TypeInfo::of::<T>(),
+            type_name: std::any::type_name::<T>(),
             last_fired: Arc::new(Mutex::new(None)),
         }
     }
 
-    pub fn add_handler<T, F, M>(&self, handler: F)
+    pub fn add_handler<T, F, M>(&self, handler: F) -> HandlerId
     where
         T: Component,
         F: IntoHandlerConfig<M, Event = T>,
         M: 'static,
     {
         assert_eq!(TypeInfo::of::<T>(), self.type_id);
-        self.handlers.insert(handler);
+        self.handlers.insert(handler)
     }
 
     pub async fn fire<T: Component>(
@@ -165,9 +262,105 @@ impl DynEventDispatcher {
             self.type_id,
             "Event Type ID mismatch; Check if you're sending the right kind of payload!"
         );
-        let event: Arc<dyn Component> = Arc::new(event);
+        self.fire_dyn(
+            world,
+            Arc::new(event),
+            EventContext::default(),
+            await_all_handlers,
+        )
+        .await
+    }
+
+    pub async fn fire_at<T: Component>(
+        &self,
+        world: WorldHandle,
+        target: Entity,
+        event: T,
+    ) -> usize {
+        assert_eq!(
+            TypeInfo::of::<T>(),
+            self.type_id,
+            "Event Type ID mismatch; Check if you're sending the right kind of payload!"
+        );
+        self.bubble_dyn(world, target, Arc::new(event)).await
+    }
 
-        let handlers = self.handlers.handlers.read().await;
+    /// Fires the event once for `target` and once for each of its ancestors, nearest first, until a handler stops it.
+    ///
+    /// Handlers that aren't observing an entity only run for `target` itself.
+    pub(crate) async fn bubble_dyn(
+        &self,
+        world: WorldHandle,
+        target: Entity,
+        event: Arc<dyn Component>,
+    ) -> usize {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let mut handlers_fired = 0;
+        let mut current_target = Some(target);
+
+        while let Some(entity) = current_target {
+            let context = EventContext {
+                propagation: Some(Propagation {
+                    target,
+                    current_target: entity,
+                    stopped: stopped.clone(),
+                }),
+                responses: None,
+            };
+            // every handler has to be done before we know whether one of them stopped it
+            handlers_fired += self
+                .fire_dyn(world.clone(), event.clone(), context, true)
+                .await;
+
+            if stopped.load(Ordering::Acquire) {
+                break;
+            }
+            current_target = world.parent_of(entity).await;
+        }
+
+        handlers_fired
+    }
+
+    /// Fires an already type-erased event. The caller is responsible for the payload's type matching this dispatcher's.
+    pub(crate) async fn fire_dyn(
+        &self,
+        world: WorldHandle,
+        event: Arc<dyn Component>,
+        context: EventContext,
+        await_all_handlers: bool,
+    ) -> usize {
+        if await_all_handlers {
+            return self.run_batches(world, event, context).await;
+        }
+
+        // still one batch after another, and one group after another within each batch,
+        // just without the caller waiting on it
+        let handler_count = self.handlers.handlers.load().node_count();
+        let mut fire = JoinSet::new();
+        fire.spawn({
+            let dispatcher = self.clone();
+            let world = world.clone();
+            async move {
+                dispatcher.run_batches(world, event, context).await;
+            }
+        });
+        world.world.read().await.run_state().detach(fire);
+        handler_count
+    }
+
+    /// Runs every handler, one batch at a time, applying each batch's commands once it's done.
+    async fn run_batches(
+        &self,
+        world: WorldHandle,
+        event: Arc<dyn Component>,
+        context: EventContext,
+    ) -> usize {
+        self.handlers.resolve_if_dirty(self.type_name);
+
+        let handlers = self.handlers.handlers.load();
+        let set_configs = self.handlers.set_configs.load();
+        // set conditions are only checked once per fire
+        let mut set_conditions_met = FxHashMap::default();
 
         // kahn's algorithm to process as many as possible at a time
 
@@ -213,34 +406,128 @@ impl DynEventDispatcher {
                 type_id: self.type_id,
                 delta_time,
                 event: event.clone(),
+                context: context.clone(),
             };
 
-            let mut join_handles = JoinSet::new();
-
+            // split the batch into groups that don't contend for the same resources or components,
+            // so handlers that would only end up waiting on each other's locks run one group after another
+            let mut groups: Vec<(EventHandlerMeta, Vec<NodeIndex>)> = Vec::new();
             for node in batch {
-                let handler = handlers[node].clone();
-                join_handles.spawn({
-                    let world = world.clone();
-                    let event = event.clone();
-                    async move {
-                        if !handler.handler.is_initialized().await {
-                            handler.handler.init(world.clone()).await;
-                        }
-
-                        if handler.meta.can_run(&world).await {
-                            handler.handler.run_dyn(world, event).await;
-                        }
+                if !handlers[node].is_enabled() {
+                    continue;
+                }
+                if !context.runs(handlers[node].observed) {
+                    continue;
+                }
+                let meta = &*handlers[node].meta;
+                match groups
+                    .iter_mut()
+                    .find(|(group_meta, _)| group_meta.is_compatible(meta))
+                {
+                    Some((group_meta, nodes)) => {
+                        *group_meta = group_meta.clone() + meta.clone();
+                        nodes.push(node);
                     }
-                });
+                    None => groups.push((meta.clone(), vec![node])),
+                }
             }
 
-            if await_all_handlers {
+            // everything the batch defers through `Commands` is applied once it's done
+            let commands = CommandQueue::default();
+
+            for (_, group) in groups {
+                let mut join_handles = JoinSet::new();
+
+                for node in group {
+                    let handler = handlers[node].clone();
+                    if !Self::conditions_met(
+                        &handler,
+                        &world,
+                        &set_configs,
+                        &mut set_conditions_met,
+                    )
+                    .await
+                    {
+                        continue;
+                    }
+
+                    let event_type = self.type_name;
+                    join_handles.spawn({
+                        let world = world.clone();
+                        let event = event.clone();
+                        let handler_run = diagnostics::handler_scope(handler.name, async move {
+                            // panics are reported like any other error instead of silently ending the task
+                            let result = AssertUnwindSafe(async {
+                                if !handler.handler.is_initialized().await {
+                                    handler.handler.init(world.clone()).await;
+                                }
+
+                                handler.handler.run_dyn(world.clone(), event).await
+                            })
+                            .catch_unwind()
+                            .await
+                            .unwrap_or_else(|payload| Err(HandlerError::from_panic(payload)));
+
+                            if let Err(error) = result {
+                                world
+                                    .report_handler_error(&handler, event_type, error)
+                                    .await;
+                            }
+                        });
+                        commands::batch_scope(commands.clone(), handler_run)
+                    });
+                }
+
                 join_handles.join_all().await;
-            } else {
-                join_handles.detach_all();
             }
+
+            world.apply_command_list(commands.close().await).await;
         }
 
         handlers.node_count()
     }
+
+    async fn conditions_met(
+        handler: &DynEventHandler,
+        world: &WorldHandle,
+        set_configs: &FxHashMap<InternedHandlerSet, HandlerSetConfig>,
+        set_conditions_met: &mut FxHashMap<InternedHandlerSet, bool>,
+    ) -> bool {
+        for set in handler.sets.iter() {
+            let met = match set_conditions_met.get(set) {
+                Some(met) => *met,
+                None => {
+                    let mut met = true;
+                    for condition in set_configs
+                        .get(set)
+                        .into_iter()
+                        .flat_map(|config| &config.conditions)
+                    {
+                        if !Self::evaluate(condition, world).await {
+                            met = false;
+                            break;
+                        }
+                    }
+                    set_conditions_met.insert(*set, met);
+                    met
+                }
+            };
+            if !met {
+                return false;
+            }
+        }
+
+        for condition in handler.conditions.iter() {
+            if !Self::evaluate(condition, world).await {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    async fn evaluate(condition: &Arc<dyn Condition>, world: &WorldHandle) -> bool {
+        // params' futures aren't `Sync`, which fires have to be
+        SyncFuture::new(condition.evaluate(world.clone())).await
+    }
 }
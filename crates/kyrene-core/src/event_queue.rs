@@ -0,0 +1,232 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use futures::future::BoxFuture;
+
+use crate::{
+    component::{Component, Mut, Ref},
+    handler::{EventHandlerMeta, HandlerParam},
+    world::World,
+    world_handle::WorldHandle,
+};
+
+/// A double-buffered queue of `T`s, read with [`EventReader`] and written with [`EventWriter`].
+///
+/// Unlike [`WorldHandle::fire_event`], sending doesn't run anything. Events stay in the queue until the
+/// second [`WorldTick`](crate::world::WorldTick) after they were sent, so every handler that reads the
+/// queue once per tick sees each event exactly once.
+pub struct EventQueue<T: Component> {
+    /// Sent before the last swap.
+    previous: Vec<T>,
+    /// Sent since the last swap.
+    current: Vec<T>,
+    /// How many events were dropped from the queue before the first one in `previous`.
+    start: u64,
+}
+
+impl<T: Component> Default for EventQueue<T> {
+    fn default() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            start: 0,
+        }
+    }
+}
+
+impl<T: Component> EventQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        self.current.extend(events);
+    }
+
+    /// How many events are buffered.
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every buffered event, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.previous.iter().chain(self.current.iter())
+    }
+
+    /// Drops the events sent before the last swap, keeping the rest around for one more.
+    pub fn swap(&mut self) {
+        self.start += self.previous.len() as u64;
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+    }
+
+    /// Drops every buffered event.
+    pub fn clear(&mut self) {
+        self.start += self.len() as u64;
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// How many events have ever been sent.
+    fn sent(&self) -> u64 {
+        self.start + self.len() as u64
+    }
+
+    /// How many of the events sent after the first `cursor` were already dropped, and the ones that weren't.
+    fn since(&self, cursor: u64) -> (u64, &[T], &[T]) {
+        let missed = self.start.saturating_sub(cursor);
+        let skip = cursor.saturating_sub(self.start) as usize;
+        if skip < self.previous.len() {
+            (missed, &self.previous[skip..], &self.current)
+        } else {
+            let skip = (skip - self.previous.len()).min(self.current.len());
+            (missed, &[], &self.current[skip..])
+        }
+    }
+}
+
+pub(crate) type SwapQueue = for<'a> fn(&'a World) -> BoxFuture<'a, ()>;
+
+pub(crate) fn swap_queue<T: Component>(world: &World) -> BoxFuture<'_, ()> {
+    Box::pin(async move {
+        if let Some(mut queue) = world.get_resource_mut::<EventQueue<T>>().await {
+            // don't flag the resource as changed for nothing
+            if !queue.is_empty() {
+                queue.swap();
+            }
+        }
+    })
+}
+
+/// Reads the events in an [`EventQueue`] that this handler hasn't read yet.
+pub struct EventReader<T: Component> {
+    queue: Ref<EventQueue<T>>,
+    cursor: Arc<AtomicU64>,
+}
+
+impl<T: Component> EventReader<T> {
+    /// Iterates over the unread events, oldest first, marking them read.
+    pub fn read(&mut self) -> impl DoubleEndedIterator<Item = &T> {
+        let cursor = self.cursor.swap(self.queue.sent(), Ordering::AcqRel);
+        let (missed, previous, current) = self.queue.since(cursor);
+        if missed > 0 {
+            tracing::warn!(
+                "EventReader<{}> missed {} events that were dropped before it read them",
+                std::any::type_name::<T>(),
+                missed
+            );
+        }
+        previous.iter().chain(current)
+    }
+
+    /// How many events haven't been read yet.
+    pub fn len(&self) -> usize {
+        let (_, previous, current) = self.queue.since(self.cursor.load(Ordering::Acquire));
+        previous.len() + current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Marks every event read without looking at them.
+    pub fn clear(&mut self) {
+        self.cursor.store(self.queue.sent(), Ordering::Release);
+    }
+}
+
+impl<T: Component> HandlerParam for EventReader<T> {
+    type Item = EventReader<T>;
+    /// How many of the queue's events the handler has read.
+    type State = Arc<AtomicU64>;
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default().res::<EventQueue<T>>()
+    }
+
+    async fn init_state(world: WorldHandle) -> Self::State {
+        world.add_event_queue::<T>().await;
+        // start at whatever is still buffered, rather than everything that was ever sent
+        let start = world
+            .get_resource::<EventQueue<T>>()
+            .await
+            .map_or(0, |queue| queue.start);
+        Arc::new(AtomicU64::new(start))
+    }
+
+    async fn fetch(world: WorldHandle, cursor: &mut Self::State) -> Option<Self::Item> {
+        Some(EventReader {
+            queue: world.get_resource::<EventQueue<T>>().await?,
+            cursor: cursor.clone(),
+        })
+    }
+
+    async fn can_run(world: WorldHandle, _: &Self::State) -> bool {
+        world.has_resource::<EventQueue<T>>().await
+    }
+}
+
+/// Sends events into an [`EventQueue`].
+pub struct EventWriter<T: Component>(Mut<EventQueue<T>>);
+
+impl<T: Component> EventWriter<T> {
+    pub fn send(&mut self, event: T) {
+        self.0.send(event);
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        self.0.send_batch(events);
+    }
+}
+
+impl<T: Component> HandlerParam for EventWriter<T> {
+    type Item = EventWriter<T>;
+    type State = ();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default().res_mut::<EventQueue<T>>()
+    }
+
+    async fn init_state(world: WorldHandle) -> Self::State {
+        world.add_event_queue::<T>().await;
+    }
+
+    async fn fetch(world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(EventWriter(
+            world.get_resource_mut::<EventQueue<T>>().await?,
+        ))
+    }
+
+    async fn can_run(world: WorldHandle, _: &()) -> bool {
+        world.has_resource::<EventQueue<T>>().await
+    }
+}
+
+impl WorldHandle {
+    /// Inserts an empty [`EventQueue<T>`] if there isn't one, and swaps it every tick from then on.
+    pub async fn add_event_queue<T: Component>(&self) {
+        if self.world.read().await.has_event_queue::<T>() {
+            return;
+        }
+        self.world.write().await.add_event_queue::<T>().await;
+    }
+
+    /// Sends an event into the [`EventQueue<T>`], adding the queue if needed.
+    pub async fn send_event<T: Component>(&self, event: T) {
+        self.add_event_queue::<T>().await;
+        self.get_resource_mut::<EventQueue<T>>()
+            .await
+            .unwrap()
+            .send(event);
+    }
+}
@@ -2,19 +2,28 @@ use std::{
     future::Future,
     marker::PhantomData,
     ops::{Add, Deref, DerefMut},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 use downcast_rs::DowncastSync;
 use futures::{future::BoxFuture, FutureExt};
+use hashbrown::hash_map::Entry;
 use petgraph::prelude::*;
 
 use crate::{
     component::Mut,
+    condition::{into_condition, Condition, ConditionFn},
+    entity::Entity,
+    error::{HandlerResult, IntoHandlerResult},
     event::{DynEvent, DynEventDispatcher, Event, EventDispatcher},
-    lock::{Read, RwLock, Write},
+    handler_graph::{type_names, HandlerGraphProblem},
+    handler_set::{HandlerSet, HandlerSetConfig, InternedHandlerSet},
+    lock::{Read, RwLock, Snapshot, Write},
     prelude::{Component, Ref},
-    util::{FxHashSet, TypeIdMap, TypeIdSet, TypeInfo},
+    util::{FxHashMap, FxHashSet, TypeIdMap, TypeIdSet, TypeInfo},
     world_handle::{FromWorldHandle, WorldHandle},
 };
 
@@ -22,6 +31,11 @@ use crate::{
 pub struct EventHandlerMeta {
     pub resources_read: TypeIdSet,
     pub resources_written: TypeIdSet,
+    pub components_read: TypeIdSet,
+    pub components_written: TypeIdSet,
+    /// Resources and components that the handler's own parameters borrow in conflicting ways,
+    /// e.g. `(Res<T>, ResMut<T>)`. Holding both borrows at once deadlocks.
+    pub conflicts: TypeIdSet,
 }
 
 impl EventHandlerMeta {
@@ -35,6 +49,16 @@ impl EventHandlerMeta {
         self
     }
 
+    pub fn component<T: Component>(mut self) -> Self {
+        self.components_read.insert_for::<T>();
+        self
+    }
+
+    pub fn component_mut<T: Component>(mut self) -> Self {
+        self.components_written.insert_for::<T>();
+        self
+    }
+
     pub fn required_resources(&self) -> impl Iterator<Item = TypeInfo> + use<'_> {
         self.resources_read
             .iter()
@@ -42,25 +66,36 @@ impl EventHandlerMeta {
             .chain(self.resources_written.iter().copied())
     }
 
+    /// Returns `true` if the two can run at the same time without contending for the same resource or component.
     pub fn is_compatible(&self, other: &Self) -> bool {
-        let mut conflicts = 0;
-
-        conflicts += self
-            .resources_read
-            .intersection(&other.resources_written)
-            .count();
-
-        conflicts += self
-            .resources_written
-            .intersection(&other.resources_read)
-            .count();
-
-        conflicts += self
-            .resources_written
-            .intersection(&other.resources_written)
-            .count();
-
-        conflicts == 0
+        self.conflicts_with(other).is_empty()
+    }
+
+    /// The resources and components one of the two writes while the other reads or writes them.
+    pub fn conflicts_with(&self, other: &Self) -> TypeIdSet {
+        let mut conflicts = TypeIdSet::default();
+        let mut add = |read: &TypeIdSet,
+                       written: &TypeIdSet,
+                       other_read: &TypeIdSet,
+                       other_written: &TypeIdSet| {
+            conflicts.extend(read.intersection(other_written).copied());
+            conflicts.extend(written.intersection(other_read).copied());
+            conflicts.extend(written.intersection(other_written).copied());
+        };
+
+        add(
+            &self.resources_read,
+            &self.resources_written,
+            &other.resources_read,
+            &other.resources_written,
+        );
+        add(
+            &self.components_read,
+            &self.components_written,
+            &other.components_read,
+            &other.components_written,
+        );
+        conflicts
     }
 
     pub async fn can_run(&self, world: &WorldHandle) -> bool {
@@ -77,8 +112,12 @@ impl Add<Self> for EventHandlerMeta {
 
     fn add(self, rhs: Self) -> Self::Output {
         let mut out = self.clone();
+        out.conflicts.extend(self.conflicts_with(&rhs));
+        out.conflicts.extend(rhs.conflicts);
         out.resources_read.extend(rhs.resources_read);
         out.resources_written.extend(rhs.resources_written);
+        out.components_read.extend(rhs.components_read);
+        out.components_written.extend(rhs.components_written);
         out
     }
 }
@@ -92,7 +131,7 @@ pub(crate) trait EventHandler: Send + Sync {
 
     fn is_initialized(&self) -> BoxFuture<'static, bool>;
 
-    fn run_dyn(&self, world: WorldHandle, event: DynEvent) -> BoxFuture<'static, ()>;
+    fn run_dyn(&self, world: WorldHandle, event: DynEvent) -> BoxFuture<'static, HandlerResult>;
 }
 
 pub trait EventHandlerFn<M>: Send + Sync + 'static {
@@ -106,7 +145,7 @@ pub trait EventHandlerFn<M>: Send + Sync + 'static {
         world: WorldHandle,
         event: Event<Self::Event>,
         param: HandlerParamItem<Self::Param>,
-    ) -> BoxFuture<'static, ()>;
+    ) -> BoxFuture<'static, HandlerResult>;
 }
 
 pub(crate) trait IntoEventHandler<M>: Send + Sync {
@@ -124,10 +163,12 @@ pub trait HandlerParam: Send + Sync {
 
     fn init_state(world: WorldHandle) -> impl Future<Output = Self::State> + Send;
 
+    /// Returns `None` if the param is gone by the time it's fetched, e.g. a resource removed after
+    /// [`can_run`](HandlerParam::can_run) passed, in which case the handler is skipped.
     fn fetch(
         world: WorldHandle,
         state: &mut Self::State,
-    ) -> impl Future<Output = Self::Item> + Send;
+    ) -> impl Future<Output = Option<Self::Item>> + Send;
 
     fn can_run(world: WorldHandle, state: &Self::State) -> impl Future<Output = bool> + Send {
         async move { true }
@@ -147,7 +188,9 @@ impl HandlerParam for () {
 
     async fn init_state(_world: WorldHandle) -> Self::State {}
 
-    async fn fetch(_world: WorldHandle, _: &mut ()) -> Self::Item {}
+    async fn fetch(_world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(())
+    }
 
     async fn can_run(_world: WorldHandle, _: &()) -> bool {
         true
@@ -175,8 +218,8 @@ impl<T: Component> HandlerParam for Res<T> {
 
     async fn init_state(_world: WorldHandle) -> Self::State {}
 
-    async fn fetch(world: WorldHandle, _: &mut ()) -> Self::Item {
-        Res(world.get_resource::<T>().await.unwrap())
+    async fn fetch(world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(Res(world.get_resource::<T>().await?))
     }
 
     async fn can_run(world: WorldHandle, _: &()) -> bool {
@@ -194,8 +237,8 @@ impl<T: Component> HandlerParam for Option<Res<T>> {
 
     async fn init_state(_world: WorldHandle) -> Self::State {}
 
-    async fn fetch(world: WorldHandle, _: &mut ()) -> Self::Item {
-        Some(Res(world.get_resource::<T>().await?))
+    async fn fetch(world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(world.get_resource::<T>().await.map(Res))
     }
 
     async fn can_run(_world: WorldHandle, _: &()) -> bool {
@@ -230,8 +273,8 @@ impl<T: Component> HandlerParam for ResMut<T> {
 
     async fn init_state(_world: WorldHandle) -> Self::State {}
 
-    async fn fetch(world: WorldHandle, _: &mut ()) -> Self::Item {
-        ResMut(world.get_resource_mut::<T>().await.unwrap())
+    async fn fetch(world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(ResMut(world.get_resource_mut::<T>().await?))
     }
 
     async fn can_run(world: WorldHandle, _: &()) -> bool {
@@ -249,8 +292,8 @@ impl<T: Component> HandlerParam for Option<ResMut<T>> {
 
     async fn init_state(_world: WorldHandle) -> Self::State {}
 
-    async fn fetch(world: WorldHandle, _: &mut ()) -> Self::Item {
-        Some(ResMut(world.get_resource_mut::<T>().await?))
+    async fn fetch(world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(world.get_resource_mut::<T>().await.map(ResMut))
     }
 
     async fn can_run(_world: WorldHandle, _: &()) -> bool {
@@ -258,6 +301,51 @@ impl<T: Component> HandlerParam for Option<ResMut<T>> {
     }
 }
 
+/// Like [`Res`], but the handler only runs if the resource was inserted or mutably dereferenced since it last ran.
+///
+/// Unlike [`ResourceChanged`](crate::resource::ResourceChanged), this checks the resource's change tick directly,
+/// so a write is seen by the next event the handler runs for, not just on the next tick.
+#[derive(Debug)]
+pub struct ResChanged<T: Component>(Ref<T>);
+
+impl<T: Component> Deref for ResChanged<T> {
+    type Target = Ref<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Component> HandlerParam for ResChanged<T> {
+    type Item = ResChanged<T>;
+    /// The change tick the handler last ran at.
+    type State = u64;
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default().res::<T>()
+    }
+
+    async fn init_state(_world: WorldHandle) -> Self::State {
+        0
+    }
+
+    async fn fetch(world: WorldHandle, last_run: &mut u64) -> Option<Self::Item> {
+        let this_run = world.change_tick().await.increment();
+        // removed since `can_run`, so keep `last_run` and check again once it's back
+        let mut res = world.get_resource::<T>().await?;
+        res.last_run = *last_run;
+        *last_run = this_run;
+        Some(ResChanged(res))
+    }
+
+    async fn can_run(world: WorldHandle, last_run: &u64) -> bool {
+        world
+            .resource_ticks::<T>()
+            .await
+            .is_some_and(|ticks| ticks.is_changed(*last_run))
+    }
+}
+
 pub struct Local<T: Component + FromWorldHandle>(Arc<RwLock<T>>);
 
 impl<T: Component + FromWorldHandle> Clone for Local<T> {
@@ -288,8 +376,8 @@ impl<T: Component + FromWorldHandle> HandlerParam for Local<T> {
         Self(Arc::new(RwLock::new(T::from_world_handle(&world).await)))
     }
 
-    async fn fetch(_world: WorldHandle, state: &mut Self::State) -> Self::Item {
-        state.clone()
+    async fn fetch(_world: WorldHandle, state: &mut Self::State) -> Option<Self::Item> {
+        Some(state.clone())
     }
 
     async fn can_run(_world: WorldHandle, _state: &Self::State) -> bool {
@@ -306,11 +394,8 @@ macro_rules! impl_handler_param_tuple {
 
             fn meta() -> EventHandlerMeta {
                 let mut meta = EventHandlerMeta::default();
-                $(
-                    let meta2 = $param::meta();
-                    assert!(meta.is_compatible(&meta2));
-                    meta = meta + meta2;
-                )*
+                // conflicts between the params are kept in the meta, and reported when the handler is added
+                $(meta = meta + $param::meta();)*
                 meta
             }
 
@@ -318,9 +403,10 @@ macro_rules! impl_handler_param_tuple {
                 tokio::join!($($param::init_state(world.clone()),)*)
             }
 
-            async fn fetch(world: WorldHandle, state: &mut Self::State) -> Self::Item {
+            async fn fetch(world: WorldHandle, state: &mut Self::State) -> Option<Self::Item> {
                 let ($($param,)*) = state;
-                tokio::join!($($param::fetch(world.clone(), $param),)*)
+                let ($($param,)*) = tokio::join!($($param::fetch(world.clone(), $param),)*);
+                Some(($($param?,)*))
             }
 
             async fn can_run(world: WorldHandle, state: &Self::State) -> bool {
@@ -376,6 +462,10 @@ impl<M, F> EventHandler for FunctionEventHandler<M, F>
 where
     F: EventHandlerFn<M>,
 {
+    fn meta(&self) -> EventHandlerMeta {
+        <F::Param>::meta()
+    }
+
     fn init(&self, world: WorldHandle) -> BoxFuture<'static, ()> {
         let func = self.func.clone();
         let state = self.state.clone();
@@ -391,18 +481,21 @@ where
         async move { state.read().await.is_some() }.boxed()
     }
 
-    fn run_dyn(&self, world: WorldHandle, event: DynEvent) -> BoxFuture<'static, ()> {
+    fn run_dyn(&self, world: WorldHandle, event: DynEvent) -> BoxFuture<'static, HandlerResult> {
         let event: Event<<F as EventHandlerFn<M>>::Event> = Event::from_dyn_event(event);
         let func = self.func.clone();
         let state = self.state.clone();
         async move {
             let mut state_lock = state.write().await;
             let state = state_lock.as_mut().unwrap();
-            if <F::Param>::can_run(world.clone(), state).await {
-                let param = <F::Param>::fetch(world.clone(), state).await;
-                drop(state_lock);
-                func.run(world, event, param).await;
+            if !<F::Param>::can_run(world.clone(), state).await {
+                return Ok(());
             }
+            let Some(param) = <F::Param>::fetch(world.clone(), state).await else {
+                return Ok(());
+            };
+            drop(state_lock);
+            func.run(world, event, param).await
         }
         .boxed()
     }
@@ -421,10 +514,11 @@ where
     }
 }
 
-impl<Func, Fut, T> EventHandlerFn<fn(WorldHandle, Event<T>)> for Func
+impl<Func, Fut, R, T> EventHandlerFn<fn(WorldHandle, Event<T>)> for Func
 where
     Func: Fn(Event<T>) -> Fut + Send + Sync + 'static,
-    Fut: Future<Output = ()> + Send + Sync + 'static,
+    Fut: Future<Output = R> + Send + Sync + 'static,
+    R: IntoHandlerResult<T>,
     T: Component,
 {
     type Event = T;
@@ -442,20 +536,24 @@ where
         _world: WorldHandle,
         event: Event<Self::Event>,
         _param: HandlerParamItem<Self::Param>,
-    ) -> BoxFuture<'static, ()> {
-        (self)(event).boxed()
+    ) -> BoxFuture<'static, HandlerResult> {
+        let responses = event.responses();
+        (self)(event)
+            .map(move |result| result.into_handler_result_with(responses.as_deref()))
+            .boxed()
     }
 }
 
 macro_rules! impl_fn_event_handler {
     ($($param:ident),*) => {
         #[allow(unused, non_snake_case)]
-        impl<Func, Fut, Event, $($param),*> EventHandlerFn<fn(Arc<Event>, $($param,)*)> for Func
+        impl<Func, Fut, R, Event, $($param),*> EventHandlerFn<fn(Arc<Event>, $($param,)*)> for Func
         where
             Func: Fn($crate::event::Event<Event>, $($param),*) -> Fut + Send + Sync + 'static
                 + Fn($crate::event::Event<Event>, $(HandlerParamItem<$param>),*) -> Fut + Send + Sync + 'static,
             $($param: HandlerParam + 'static),*,
-            Fut: Future<Output = ()> + Send + Sync + 'static,
+            Fut: Future<Output = R> + Send + Sync + 'static,
+            R: IntoHandlerResult<Event>,
             Event: $crate::component::Component,
         {
             type Event = Event;
@@ -470,9 +568,12 @@ macro_rules! impl_fn_event_handler {
                 _world: WorldHandle,
                 event: $crate::event::Event<Self::Event>,
                 param: HandlerParamItem<Self::Param>,
-            ) -> BoxFuture<'static, ()> {
+            ) -> BoxFuture<'static, HandlerResult> {
                 let ($($param),*) = param;
-                (self)(event, $($param),*).boxed()
+                let responses = event.responses();
+                (self)(event, $($param),*)
+                    .map(move |result| result.into_handler_result_with(responses.as_deref()))
+                    .boxed()
             }
         }
     };
@@ -495,29 +596,91 @@ impl_fn_event_handler!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
 impl_fn_event_handler!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
 impl_fn_event_handler!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
 
+static NEXT_HANDLER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies an event handler added to the world, for removing or disabling it later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HandlerId {
+    event_type: TypeInfo,
+    id: u64,
+}
+
+impl HandlerId {
+    fn new(event_type: TypeInfo) -> Self {
+        Self {
+            event_type,
+            id: NEXT_HANDLER_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// The event the handler handles.
+    pub fn event_type(&self) -> TypeInfo {
+        self.event_type
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct DynEventHandler {
+    pub id: HandlerId,
+    pub name: &'static str,
     pub handler: Arc<dyn EventHandler>,
     pub meta: Arc<EventHandlerMeta>,
+    pub enabled: Arc<AtomicBool>,
+    pub conditions: Arc<[Arc<dyn Condition>]>,
+    pub sets: Arc<[InternedHandlerSet]>,
+    /// Only runs for events that have bubbled up to this entity, if set.
+    pub observed: Option<Entity>,
+    /// Ordering constraints, resolved into edges the next time the event is fired.
+    pub options: Arc<[HandlerAddOption]>,
+}
+
+impl DynEventHandler {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+    }
 }
 
+/// Why there's an edge between two handlers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandlerEdge {
+    /// Ordered against each other directly with `after`/`before`.
+    Explicit,
+    /// Ordered through a [`HandlerSet`] one of them is in.
+    Set,
+}
+
+/// An event's handlers, with edges for the order they run in.
+pub(crate) type HandlerNodes = StableDiGraph<DynEventHandler, HandlerEdge>;
+
 #[derive(Clone)]
 pub(crate) struct DynEventHandlers {
     pub event_type_id: TypeInfo,
-    pub handlers: Arc<RwLock<StableDiGraph<DynEventHandler, ()>>>,
-    pub index_cache: Arc<RwLock<TypeIdMap<NodeIndex>>>,
+    /// Every fire runs on a snapshot, so handlers can be added and removed at any time, even
+    /// from the event's own handlers. Changes take effect the next time the event is fired.
+    pub handlers: Snapshot<HandlerNodes>,
+    pub index_cache: Snapshot<TypeIdMap<NodeIndex>>,
+    pub set_configs: Snapshot<FxHashMap<InternedHandlerSet, HandlerSetConfig>>,
+    /// Set whenever the edges no longer reflect the handlers' ordering constraints.
+    pub dirty: Arc<AtomicBool>,
 }
 
 impl DynEventHandlers {
     pub fn new<T: Component>() -> Self {
         Self {
             event_type_id: TypeInfo::of::<T>(),
-            handlers: Arc::new(RwLock::new(StableDiGraph::new())),
-            index_cache: Arc::new(RwLock::new(TypeIdMap::default())),
+            handlers: Snapshot::default(),
+            index_cache: Snapshot::default(),
+            set_configs: Snapshot::default(),
+            dirty: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    pub fn insert<T, F, M>(&self, handler: F) -> NodeIndex
+    pub fn insert<T, F, M>(&self, handler: F) -> HandlerId
     where
         T: Component,
         F: IntoHandlerConfig<M, Event = T>,
@@ -525,30 +688,91 @@ impl DynEventHandlers {
     {
         assert_eq!(TypeInfo::of::<T>(), self.event_type_id);
         let config = handler.finish();
-        let index = self.handlers.blocking_write().add_node(DynEventHandler {
+        if !config.meta.conflicts.is_empty() {
+            tracing::error!(
+                "{}",
+                HandlerGraphProblem::ConflictingParams {
+                    event_type: std::any::type_name::<T>(),
+                    handler: config.handler_name,
+                    types: type_names(&config.meta.conflicts),
+                }
+            );
+        }
+        let id = HandlerId::new(self.event_type_id);
+        let handler = DynEventHandler {
+            id,
+            name: config.handler_name,
             handler: config.handler,
             meta: config.meta,
+            enabled: Arc::new(AtomicBool::new(true)),
+            conditions: config.conditions.into(),
+            sets: config.sets.into(),
+            observed: config.observed,
+            options: config.options.into_iter().collect(),
+        };
+        let index = self.handlers.update(|handlers| handlers.add_node(handler));
+        self.index_cache
+            .update(|index_cache| index_cache.insert(config.handler_type_id, index));
+
+        // whatever this is ordered against may not have been added yet
+        self.dirty.store(true, Ordering::Release);
+
+        id
+    }
+
+    /// Removes the handler, along with any ordering constraints involving it.
+    ///
+    /// A fire of this event that's already in progress still runs it.
+    pub fn remove(&self, id: HandlerId) -> bool {
+        let removed = self.handlers.update(|handlers| {
+            let index = Self::find(handlers, id)?;
+            handlers.remove_node(index);
+            Some(index)
         });
+        let Some(index) = removed else {
+            return false;
+        };
+        // the index may be reused, so nothing can keep pointing at it
         self.index_cache
-            .blocking_write()
-            .insert(config.handler_type_id, index);
-
-        for opt in config.options {
-            let mut handlers = self.handlers.blocking_write();
-            let index_cache = self.index_cache.blocking_read();
-            match opt {
-                HandlerAddOption::After(first) => {
-                    let first = *index_cache.get(&first).unwrap();
-                    handlers.add_edge(first, index, ());
-                }
-                HandlerAddOption::Before(second) => {
-                    let second = *index_cache.get(&second).unwrap();
-                    handlers.add_edge(index, second, ());
+            .update(|index_cache| index_cache.retain(|_, cached| *cached != index));
+        self.dirty.store(true, Ordering::Release);
+        true
+    }
+
+    /// Returns `false` if there's no such handler.
+    pub fn set_enabled(&self, id: HandlerId, enabled: bool) -> bool {
+        let handlers = self.handlers.load();
+        let Some(index) = Self::find(&handlers, id) else {
+            return false;
+        };
+        handlers[index].set_enabled(enabled);
+        true
+    }
+
+    pub fn ids(&self) -> Vec<HandlerId> {
+        let handlers = self.handlers.load();
+        handlers
+            .node_indices()
+            .map(|index| handlers[index].id)
+            .collect()
+    }
+
+    fn find(handlers: &HandlerNodes, id: HandlerId) -> Option<NodeIndex> {
+        handlers
+            .node_indices()
+            .find(|index| handlers[*index].id == id)
+    }
+
+    pub fn configure_set(&self, config: HandlerSetConfig) {
+        self.set_configs
+            .update(|set_configs| match set_configs.entry(config.set) {
+                Entry::Occupied(mut entry) => entry.get_mut().merge(config),
+                Entry::Vacant(entry) => {
+                    entry.insert(config);
                 }
-            }
-        }
+            });
 
-        index
+        self.dirty.store(true, Ordering::Release);
     }
 }
 
@@ -556,13 +780,19 @@ impl DynEventHandlers {
 pub enum HandlerAddOption {
     After(TypeInfo),
     Before(TypeInfo),
+    AfterSet(InternedHandlerSet),
+    BeforeSet(InternedHandlerSet),
 }
 
 pub struct HandlerConfig<T: Component> {
     handler_type_id: TypeInfo,
+    handler_name: &'static str,
     handler: Arc<dyn EventHandler>,
     meta: Arc<EventHandlerMeta>,
     options: FxHashSet<HandlerAddOption>,
+    conditions: Vec<Arc<dyn Condition>>,
+    sets: Vec<InternedHandlerSet>,
+    observed: Option<Entity>,
     _marker: PhantomData<T>,
 }
 
@@ -575,9 +805,13 @@ impl<T: Component> HandlerConfig<T> {
         let handler = handler.into_event_handler();
         Self {
             handler_type_id: TypeInfo::of::<F>(),
+            handler_name: std::any::type_name::<F>(),
             meta: Arc::new(handler.meta()),
             handler,
             options: FxHashSet::default(),
+            conditions: Vec::new(),
+            sets: Vec::new(),
+            observed: None,
             _marker: PhantomData,
         }
     }
@@ -601,6 +835,42 @@ impl<T: Component> HandlerConfig<T> {
             .insert(HandlerAddOption::Before(TypeInfo::of::<F2>()));
         self
     }
+
+    /// Runs this handler after every handler in `set`.
+    pub fn after_set(mut self, set: impl HandlerSet) -> Self {
+        self.options
+            .insert(HandlerAddOption::AfterSet(set.intern()));
+        self
+    }
+
+    /// Runs this handler before every handler in `set`.
+    pub fn before_set(mut self, set: impl HandlerSet) -> Self {
+        self.options
+            .insert(HandlerAddOption::BeforeSet(set.intern()));
+        self
+    }
+
+    /// Adds this handler to `set`, so it's ordered and conditioned along with the rest of it.
+    pub fn in_set(mut self, set: impl HandlerSet) -> Self {
+        self.sets.push(set.intern());
+        self
+    }
+
+    /// Only runs this handler for events that have bubbled up to `entity`.
+    pub(crate) fn observing(mut self, entity: Entity) -> Self {
+        self.observed = Some(entity);
+        self
+    }
+
+    /// Skips this handler unless `condition` returns `true`. Checked every time the event is fired.
+    pub fn run_if<C, MC>(mut self, condition: C) -> Self
+    where
+        C: ConditionFn<MC>,
+        MC: 'static,
+    {
+        self.conditions.push(into_condition(condition));
+        self
+    }
 }
 
 pub trait IntoHandlerConfig<M>: Sized + 'static {
@@ -623,6 +893,26 @@ pub trait IntoHandlerConfig<M>: Sized + 'static {
     {
         self.finish().before(handler)
     }
+
+    fn after_set(self, set: impl HandlerSet) -> HandlerConfig<Self::Event> {
+        self.finish().after_set(set)
+    }
+
+    fn before_set(self, set: impl HandlerSet) -> HandlerConfig<Self::Event> {
+        self.finish().before_set(set)
+    }
+
+    fn in_set(self, set: impl HandlerSet) -> HandlerConfig<Self::Event> {
+        self.finish().in_set(set)
+    }
+
+    fn run_if<C, MC>(self, condition: C) -> HandlerConfig<Self::Event>
+    where
+        C: ConditionFn<MC>,
+        MC: 'static,
+    {
+        self.finish().run_if(condition)
+    }
 }
 
 impl<T, F, M> IntoHandlerConfig<M> for F
@@ -670,13 +960,17 @@ impl Events {
         self.entries.contains_type::<T>()
     }
 
-    pub fn add_handler<T, F, M>(&mut self, handler: F)
+    pub fn add_handler<T, F, M>(&mut self, handler: F) -> HandlerId
     where
         T: Component,
         F: IntoHandlerConfig<M, Event = T>,
         M: 'static,
     {
         let event = self.add_event::<T>();
-        event.add_handler(handler);
+        event.add_handler(handler)
+    }
+
+    pub(crate) fn handlers_of(&self, id: HandlerId) -> Option<DynEventHandlers> {
+        Some(self.entries.get(&id.event_type)?.handlers.clone())
     }
 }
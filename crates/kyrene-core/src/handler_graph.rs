@@ -0,0 +1,344 @@
+use std::{
+    fmt::{Display, Write},
+    sync::atomic::Ordering,
+};
+
+use itertools::Itertools;
+use petgraph::{algo::tarjan_scc, prelude::*};
+use serde::Serialize;
+
+use crate::{
+    handler::{DynEventHandlers, HandlerAddOption, HandlerEdge, HandlerNodes},
+    handler_set::{HandlerSetConfig, InternedHandlerSet},
+    util::{FxHashMap, TypeIdMap, TypeIdSet},
+};
+
+/// Something wrong with the way an event's handlers are ordered.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum HandlerGraphProblem {
+    /// A handler is ordered against one that isn't registered for the event. The constraint is ignored.
+    #[error("handler `{handler}` for `{event_type}` is ordered against `{dependency}`, which isn't registered for it")]
+    MissingDependency {
+        event_type: &'static str,
+        handler: &'static str,
+        dependency: String,
+    },
+    /// Handlers that are ordered after each other. None of them can go first, so they never run.
+    #[error("handlers for `{event_type}` are ordered in a cycle and will never run: {}", .handlers.join(", "))]
+    Cycle {
+        event_type: &'static str,
+        handlers: Vec<&'static str>,
+    },
+    /// A handler whose parameters borrow the same resource or component in conflicting ways.
+    /// It still runs, but deadlocks if it holds both borrows at once.
+    #[error("handler `{handler}` for `{event_type}` has parameters that conflict over {}; holding both borrows at once deadlocks", .types.join(", "))]
+    ConflictingParams {
+        event_type: &'static str,
+        handler: &'static str,
+        types: Vec<String>,
+    },
+}
+
+/// Everything wrong with the handler ordering of every event in a world, from
+/// [`World::validate_handlers`](crate::world::World::validate_handlers).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HandlerValidationReport {
+    pub problems: Vec<HandlerGraphProblem>,
+}
+
+impl HandlerValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn cycles(&self) -> impl Iterator<Item = &[&'static str]> {
+        self.problems.iter().filter_map(|problem| match problem {
+            HandlerGraphProblem::Cycle { handlers, .. } => Some(handlers.as_slice()),
+            _ => None,
+        })
+    }
+
+    pub fn missing_dependencies(&self) -> impl Iterator<Item = &HandlerGraphProblem> {
+        self.problems
+            .iter()
+            .filter(|problem| matches!(problem, HandlerGraphProblem::MissingDependency { .. }))
+    }
+}
+
+impl Display for HandlerValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_ok() {
+            return write!(f, "no problems with handler ordering");
+        }
+        for problem in &self.problems {
+            writeln!(f, "{problem}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot of one event's handlers and the order they run in, from
+/// [`World::handler_graph`](crate::world::World::handler_graph).
+#[derive(Clone, Debug, Serialize)]
+pub struct HandlerGraph {
+    pub event_type: &'static str,
+    pub nodes: Vec<HandlerNode>,
+    pub edges: Vec<HandlerGraphEdge>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HandlerNode {
+    /// Only meaningful within the graph it came from.
+    pub index: usize,
+    pub name: &'static str,
+    pub enabled: bool,
+    pub resources_read: Vec<String>,
+    pub resources_written: Vec<String>,
+}
+
+/// `from` runs before `to`.
+#[derive(Clone, Debug, Serialize)]
+pub struct HandlerGraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: HandlerEdge,
+}
+
+impl HandlerGraph {
+    /// Renders the graph as a Graphviz `digraph`. Edges that come from [`HandlerSet`](crate::handler_set::HandlerSet)s are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot).unwrap();
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    fn write_dot(&self, dot: &mut String) -> std::fmt::Result {
+        writeln!(dot, "digraph \"{}\" {{", escape_dot(self.event_type))?;
+        writeln!(dot, "    label=\"{}\";", escape_dot(self.event_type))?;
+        writeln!(dot, "    node [shape=box];")?;
+        for node in &self.nodes {
+            let mut label = escape_dot(node.name);
+            if !node.resources_read.is_empty() {
+                write!(
+                    label,
+                    "\\nreads: {}",
+                    escape_dot(&node.resources_read.join(", "))
+                )?;
+            }
+            if !node.resources_written.is_empty() {
+                write!(
+                    label,
+                    "\\nwrites: {}",
+                    escape_dot(&node.resources_written.join(", "))
+                )?;
+            }
+            let style = if node.enabled { "solid" } else { "dotted" };
+            writeln!(
+                dot,
+                "    h{} [label=\"{}\", style={}];",
+                node.index, label, style
+            )?;
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                HandlerEdge::Explicit => "solid",
+                HandlerEdge::Set => "dashed",
+            };
+            writeln!(dot, "    h{} -> h{} [style={}];", edge.from, edge.to, style)?;
+        }
+        writeln!(dot, "}}")
+    }
+}
+
+/// Every event's [`HandlerGraph`], from [`World::dump_all_handler_graphs`](crate::world::World::dump_all_handler_graphs).
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct HandlerGraphs {
+    pub graphs: Vec<HandlerGraph>,
+}
+
+impl HandlerGraphs {
+    /// Renders each graph as its own `digraph`, one after another, which `dot` renders as separate pages.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        for graph in &self.graphs {
+            graph.write_dot(&mut dot).unwrap();
+        }
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub(crate) fn type_names(types: &TypeIdSet) -> Vec<String> {
+    types
+        .iter()
+        .map(|type_info| type_info.type_name.to_string())
+        .sorted()
+        .collect()
+}
+
+impl DynEventHandlers {
+    /// Snapshots the handlers, with their ordering constraints resolved.
+    pub(crate) fn graph(&self, event_type: &'static str) -> HandlerGraph {
+        self.resolve_if_dirty(event_type);
+
+        let handlers = self.handlers.load();
+        let nodes = handlers
+            .node_indices()
+            .map(|node| {
+                let handler = &handlers[node];
+                HandlerNode {
+                    index: node.index(),
+                    name: handler.name,
+                    enabled: handler.is_enabled(),
+                    resources_read: type_names(&handler.meta.resources_read),
+                    resources_written: type_names(&handler.meta.resources_written),
+                }
+            })
+            .collect();
+        let edges = handlers
+            .edge_indices()
+            .map(|edge| {
+                let (from, to) = handlers.edge_endpoints(edge).unwrap();
+                HandlerGraphEdge {
+                    from: from.index(),
+                    to: to.index(),
+                    kind: handlers[edge],
+                }
+            })
+            .collect();
+
+        HandlerGraph {
+            event_type,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Resolves the ordering constraints if anything changed since they last were, logging any problems.
+    pub(crate) fn resolve_if_dirty(&self, event_type: &'static str) {
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        for problem in self.resolve(event_type) {
+            // already reported when the handler was added
+            if !matches!(problem, HandlerGraphProblem::ConflictingParams { .. }) {
+                tracing::error!("{problem}");
+            }
+        }
+    }
+
+    /// Replaces every edge with ones reflecting the handlers' current ordering constraints and sets.
+    pub(crate) fn resolve(&self, event_type: &'static str) -> Vec<HandlerGraphProblem> {
+        let index_cache = self.index_cache.load();
+        let set_configs = self.set_configs.load();
+        self.handlers.update(|handlers| {
+            Self::resolve_edges(handlers, &index_cache, &set_configs, event_type)
+        })
+    }
+
+    fn resolve_edges(
+        handlers: &mut HandlerNodes,
+        index_cache: &TypeIdMap<NodeIndex>,
+        set_configs: &FxHashMap<InternedHandlerSet, HandlerSetConfig>,
+        event_type: &'static str,
+    ) -> Vec<HandlerGraphProblem> {
+        handlers.clear_edges();
+
+        let mut problems = Vec::new();
+
+        for handler in handlers.node_weights() {
+            if !handler.meta.conflicts.is_empty() {
+                problems.push(HandlerGraphProblem::ConflictingParams {
+                    event_type,
+                    handler: handler.name,
+                    types: type_names(&handler.meta.conflicts),
+                });
+            }
+        }
+
+        let mut members: FxHashMap<InternedHandlerSet, Vec<NodeIndex>> = FxHashMap::default();
+        for node in handlers.node_indices() {
+            for set in handlers[node].sets.iter() {
+                members.entry(*set).or_default().push(node);
+            }
+        }
+        let members_of = |set: &InternedHandlerSet| members.get(set).into_iter().flatten().copied();
+
+        let mut explicit_edges = Vec::new();
+        let mut set_edges = Vec::new();
+        for node in handlers.node_indices() {
+            for opt in handlers[node].options.iter() {
+                match opt {
+                    HandlerAddOption::After(other) | HandlerAddOption::Before(other) => {
+                        let Some(&other_node) = index_cache.get(other) else {
+                            problems.push(HandlerGraphProblem::MissingDependency {
+                                event_type,
+                                handler: handlers[node].name,
+                                dependency: other.type_name.to_string(),
+                            });
+                            continue;
+                        };
+                        if matches!(opt, HandlerAddOption::After(_)) {
+                            explicit_edges.push((other_node, node));
+                        } else {
+                            explicit_edges.push((node, other_node));
+                        }
+                    }
+                    HandlerAddOption::AfterSet(set) => {
+                        set_edges.extend(members_of(set).map(|member| (member, node)));
+                    }
+                    HandlerAddOption::BeforeSet(set) => {
+                        set_edges.extend(members_of(set).map(|member| (node, member)));
+                    }
+                }
+            }
+        }
+        for config in set_configs.values() {
+            for first in config.after.iter() {
+                set_edges.extend(members_of(first).cartesian_product(members_of(&config.set)));
+            }
+            for second in config.before.iter() {
+                set_edges.extend(members_of(&config.set).cartesian_product(members_of(second)));
+            }
+        }
+
+        for (first, second) in explicit_edges {
+            if !handlers.contains_edge(first, second) {
+                handlers.add_edge(first, second, HandlerEdge::Explicit);
+            }
+        }
+        for (first, second) in set_edges {
+            // a handler in both sets isn't ordered against itself
+            if first != second && !handlers.contains_edge(first, second) {
+                handlers.add_edge(first, second, HandlerEdge::Set);
+            }
+        }
+
+        for component in tarjan_scc(&*handlers) {
+            let is_cycle = match component.as_slice() {
+                [node] => handlers.contains_edge(*node, *node),
+                _ => true,
+            };
+            if is_cycle {
+                problems.push(HandlerGraphProblem::Cycle {
+                    event_type,
+                    handlers: component.iter().map(|node| handlers[*node].name).collect(),
+                });
+            }
+        }
+
+        problems
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::{
+    condition::{into_condition, Condition, ConditionFn},
+    define_label,
+    intern::Interned,
+};
+
+define_label!(
+    /// A named group of event handlers that can be ordered and conditioned as a whole.
+    ///
+    /// Derive it with `#[derive(HandlerSet)]`, then add handlers with [`IntoHandlerConfig::in_set`](crate::handler::IntoHandlerConfig::in_set)
+    /// and configure it with [`World::configure_set`](crate::world::World::configure_set).
+    HandlerSet,
+    HANDLER_SET_INTERNER
+);
+
+pub type InternedHandlerSet = Interned<dyn HandlerSet>;
+
+/// Ordering and run conditions for every handler in a [`HandlerSet`], for a single event type.
+#[derive(Clone)]
+pub struct HandlerSetConfig {
+    pub(crate) set: InternedHandlerSet,
+    pub(crate) after: Vec<InternedHandlerSet>,
+    pub(crate) before: Vec<InternedHandlerSet>,
+    pub(crate) conditions: Vec<Arc<dyn Condition>>,
+}
+
+impl HandlerSetConfig {
+    pub fn new(set: impl HandlerSet) -> Self {
+        Self {
+            set: set.intern(),
+            after: Vec::new(),
+            before: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: HandlerSetConfig) {
+        self.after.extend(other.after);
+        self.before.extend(other.before);
+        self.conditions.extend(other.conditions);
+    }
+}
+
+pub trait IntoHandlerSetConfig: Sized {
+    fn into_config(self) -> HandlerSetConfig;
+
+    /// Runs every handler in this set after every handler in `set`.
+    fn after(self, set: impl HandlerSet) -> HandlerSetConfig {
+        let mut config = self.into_config();
+        config.after.push(set.intern());
+        config
+    }
+
+    /// Runs every handler in this set before every handler in `set`.
+    fn before(self, set: impl HandlerSet) -> HandlerSetConfig {
+        let mut config = self.into_config();
+        config.before.push(set.intern());
+        config
+    }
+
+    /// Skips every handler in this set unless `condition` returns `true`.
+    ///
+    /// The condition is checked at most once each time the event is fired.
+    fn run_if<C, M>(self, condition: C) -> HandlerSetConfig
+    where
+        C: ConditionFn<M>,
+        M: 'static,
+    {
+        let mut config = self.into_config();
+        config.conditions.push(into_condition(condition));
+        config
+    }
+}
+
+impl<T: HandlerSet> IntoHandlerSetConfig for T {
+    fn into_config(self) -> HandlerSetConfig {
+        HandlerSetConfig::new(self)
+    }
+}
+
+impl IntoHandlerSetConfig for HandlerSetConfig {
+    fn into_config(self) -> HandlerSetConfig {
+        self
+    }
+}
@@ -0,0 +1,271 @@
+use std::{collections::VecDeque, ops::Deref};
+
+use futures::Stream;
+use smallvec::{smallvec, SmallVec};
+
+use crate::{entity::Entity, world::World, world_handle::WorldHandle};
+
+/// The entity this one is a child of.
+///
+/// Kept in sync with the parent's [`Children`] by [`World::add_child`] and friends, so don't insert it by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Parent(Entity);
+
+impl Parent {
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+/// The entities that are children of this one, in the order they were added.
+///
+/// Kept in sync with their [`Parent`]s by [`World::add_child`] and friends, so don't insert it by hand.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Children(SmallVec<[Entity; 8]>);
+
+impl Children {
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + use<'_> {
+        self.0.iter().copied()
+    }
+}
+
+impl Deref for Children {
+    type Target = [Entity];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Why [`World::add_child`] refused to change the hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum HierarchyError {
+    #[error("{0:?} is dead")]
+    DeadEntity(Entity),
+    /// The child is the parent itself, or one of its ancestors.
+    #[error("{child:?} can't be made a child of its own descendant {parent:?}")]
+    Cycle { parent: Entity, child: Entity },
+}
+
+impl World {
+    pub async fn parent_of(&self, entity: Entity) -> Option<Entity> {
+        Some(self.get::<Parent>(entity).await?.get())
+    }
+
+    pub async fn children_of(&self, entity: Entity) -> Vec<Entity> {
+        match self.get::<Children>(entity).await {
+            Some(children) => children.to_vec(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the entity's parent, its parent's parent, and so on up to the root.
+    pub async fn ancestors_of(&self, entity: Entity) -> Vec<Entity> {
+        let mut ancestors = Vec::new();
+        let mut current = entity;
+        while let Some(parent) = self.parent_of(current).await {
+            ancestors.push(parent);
+            current = parent;
+        }
+        ancestors
+    }
+
+    /// Returns every entity below this one in the hierarchy, breadth-first.
+    pub async fn descendants_of(&self, entity: Entity) -> Vec<Entity> {
+        let mut descendants = Vec::new();
+        let mut queue = VecDeque::from([entity]);
+        while let Some(current) = queue.pop_front() {
+            for child in self.children_of(current).await {
+                descendants.push(child);
+                queue.push_back(child);
+            }
+        }
+        descendants
+    }
+
+    /// Makes `child` a child of `parent`, detaching it from its previous parent if it had one.
+    ///
+    /// Leaves the hierarchy untouched if either entity is dead, or if this would make an entity its own ancestor.
+    pub async fn add_child(&mut self, parent: Entity, child: Entity) -> Result<(), HierarchyError> {
+        for entity in [parent, child] {
+            if !self.is_alive(entity) {
+                return Err(HierarchyError::DeadEntity(entity));
+            }
+        }
+        if parent == child || self.ancestors_of(parent).await.contains(&child) {
+            return Err(HierarchyError::Cycle { parent, child });
+        }
+
+        match self.parent_of(child).await {
+            Some(old_parent) if old_parent == parent => return Ok(()),
+            Some(old_parent) => self.detach_child(old_parent, child).await,
+            None => {}
+        }
+
+        self.insert(child, Parent(parent)).await;
+
+        if let Some(mut children) = self.get_mut::<Children>(parent).await {
+            children.0.push(child);
+            return Ok(());
+        }
+        self.insert(parent, Children(smallvec![child])).await;
+        Ok(())
+    }
+
+    /// Detaches `child` from `parent`, leaving it as a root.
+    ///
+    /// Returns `false` if `child` wasn't a child of `parent`.
+    pub async fn remove_child(&mut self, parent: Entity, child: Entity) -> bool {
+        if self.parent_of(child).await != Some(parent) {
+            return false;
+        }
+
+        self.remove::<Parent>(child).await;
+        self.detach_child(parent, child).await;
+        true
+    }
+
+    /// Moves `child` under `parent`, or makes it a root if `parent` is `None`.
+    ///
+    /// Fails like [`World::add_child`] when moving it under `parent`.
+    pub async fn set_parent(
+        &mut self,
+        child: Entity,
+        parent: Option<Entity>,
+    ) -> Result<(), HierarchyError> {
+        match parent {
+            Some(parent) => self.add_child(parent, child).await,
+            None => {
+                if let Some(old_parent) = self.parent_of(child).await {
+                    self.remove_child(old_parent, child).await;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Despawns the entity along with all of its descendants.
+    ///
+    /// Returns `false` if the entity was already dead.
+    pub async fn despawn_recursive(&mut self, entity: Entity) -> bool {
+        // leaves first, so nothing is ever left pointing at a dead entity
+        for descendant in self.descendants_of(entity).await.into_iter().rev() {
+            self.despawn(descendant).await;
+        }
+        self.despawn(entity).await
+    }
+
+    /// Removes the entity from its parent's [`Children`] and orphans its own children, ahead of it being despawned.
+    pub(crate) async fn detach_from_hierarchy(&mut self, entity: Entity) {
+        if let Some(parent) = self.parent_of(entity).await {
+            self.detach_child(parent, entity).await;
+        }
+
+        for child in self.children_of(entity).await {
+            self.remove::<Parent>(child).await;
+        }
+    }
+
+    async fn detach_child(&mut self, parent: Entity, child: Entity) {
+        let now_empty = match self.get_mut::<Children>(parent).await {
+            Some(mut children) => {
+                children.0.retain(|other| *other != child);
+                children.is_empty()
+            }
+            None => return,
+        };
+
+        if now_empty {
+            self.remove::<Children>(parent).await;
+        }
+    }
+}
+
+impl WorldHandle {
+    pub async fn parent_of(&self, entity: Entity) -> Option<Entity> {
+        Some(self.get::<Parent>(entity).await?.get())
+    }
+
+    pub async fn children_of(&self, entity: Entity) -> Vec<Entity> {
+        match self.get::<Children>(entity).await {
+            Some(children) => children.to_vec(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Streams the entity's parent, its parent's parent, and so on up to the root.
+    pub fn ancestors(&self, entity: Entity) -> impl Stream<Item = Entity> + Send {
+        futures::stream::unfold((self.clone(), entity), |(world, current)| async move {
+            let parent = world.parent_of(current).await?;
+            Some((parent, (world, parent)))
+        })
+    }
+
+    /// Streams every entity below this one in the hierarchy, breadth-first.
+    pub fn descendants(&self, entity: Entity) -> impl Stream<Item = Entity> + Send {
+        let queue = VecDeque::from(vec![(entity, false)]);
+        futures::stream::unfold((self.clone(), queue), |(world, mut queue)| async move {
+            loop {
+                let (current, is_descendant) = queue.pop_front()?;
+                queue.extend(
+                    world
+                        .children_of(current)
+                        .await
+                        .into_iter()
+                        .map(|child| (child, true)),
+                );
+                if is_descendant {
+                    return Some((current, (world, queue)));
+                }
+            }
+        })
+    }
+
+    /// See [`World::add_child`].
+    pub async fn add_child(&self, parent: Entity, child: Entity) -> Result<(), HierarchyError> {
+        let (result, events) = {
+            let mut world = self.world.write().await;
+            let result = world.add_child(parent, child).await;
+            (result, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        result
+    }
+
+    /// See [`World::remove_child`].
+    pub async fn remove_child(&self, parent: Entity, child: Entity) -> bool {
+        let (removed, events) = {
+            let mut world = self.world.write().await;
+            let removed = world.remove_child(parent, child).await;
+            (removed, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        removed
+    }
+
+    /// See [`World::set_parent`].
+    pub async fn set_parent(
+        &self,
+        child: Entity,
+        parent: Option<Entity>,
+    ) -> Result<(), HierarchyError> {
+        let (result, events) = {
+            let mut world = self.world.write().await;
+            let result = world.set_parent(child, parent).await;
+            (result, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        result
+    }
+
+    /// See [`World::despawn_recursive`].
+    pub async fn despawn_recursive(&self, entity: Entity) -> bool {
+        let (despawned, events) = {
+            let mut world = self.world.write().await;
+            let despawned = world.despawn_recursive(entity).await;
+            (despawned, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        despawned
+    }
+}
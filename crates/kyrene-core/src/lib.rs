@@ -1,16 +1,30 @@
 use std::future::IntoFuture;
 
+pub mod change_detection;
+pub mod commands;
 pub mod component;
+pub mod condition;
+pub mod diagnostics;
 pub mod entity;
+pub mod error;
 #[macro_use]
 pub mod event;
+pub mod event_queue;
 pub mod handler;
+pub mod handler_graph;
+pub mod handler_set;
+pub mod hierarchy;
 pub mod intern;
 pub mod label;
 pub mod lock;
 pub mod plugin;
 pub mod query;
+pub mod request;
 pub mod resource;
+pub mod runner;
+pub mod storage;
+pub mod time;
+pub mod timer;
 #[macro_use]
 pub mod util;
 pub mod bundle;
@@ -23,7 +37,7 @@ pub extern crate tokio;
 #[doc(hidden)]
 pub extern crate self as kyrene_core;
 
-pub use kyrene_macro::Bundle;
+pub use kyrene_macro::{Bundle, HandlerSet};
 
 pub mod prelude {
     pub use crate::{
@@ -31,15 +45,19 @@ pub mod prelude {
         component::{Component, Ref},
         entity::Entity,
         event::{Event, EventDispatcher},
+        event_queue::{EventReader, EventWriter},
         handler::IntoHandlerConfig,
+        handler_set::{HandlerSet, IntoHandlerSetConfig},
         lock::{MappedMutexGuard, Mutex, MutexGuard},
         plugin::Plugin,
+        request::{Request, Response},
+        runner::AppExit,
         util::{FxHashMap, FxHashSet, TypeIdMap, TypeIdSet},
         world::{World, WorldTick},
         world_handle::WorldHandle,
     };
     pub use futures::StreamExt;
-    pub use kyrene_macro::Bundle;
+    pub use kyrene_macro::{Bundle, HandlerSet};
     pub use std::sync::Arc;
     pub use tokio;
     pub use tracing::{debug, error, info, trace, warn};
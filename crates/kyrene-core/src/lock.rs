@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 pub type Mutex<T> = tokio::sync::Mutex<T>;
 pub type MutexGuard<'a, T> = tokio::sync::MutexGuard<'a, T>;
 pub type OwnedMutexGuard<T> = tokio::sync::OwnedMutexGuard<T>;
@@ -9,3 +11,31 @@ pub type Read<T> = tokio::sync::OwnedRwLockReadGuard<T>;
 pub type RwLockWriteGuard<'a, T> = tokio::sync::RwLockWriteGuard<'a, T>;
 pub type Write<T> = tokio::sync::OwnedRwLockWriteGuard<T>;
 pub type RwLockMappedWriteGuard<'a, T> = tokio::sync::RwLockMappedWriteGuard<'a, T>;
+
+/// A value that's read far more often than it changes, shared without readers ever holding a lock.
+///
+/// Readers take an [`Arc`] of the current value, which stays the same however long they keep it.
+/// Writers copy the value first if a reader still has it, so they never wait on one either.
+pub(crate) struct Snapshot<T>(Arc<std::sync::RwLock<Arc<T>>>);
+
+impl<T> Clone for Snapshot<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Default> Default for Snapshot<T> {
+    fn default() -> Self {
+        Self(Arc::new(std::sync::RwLock::new(Arc::new(T::default()))))
+    }
+}
+
+impl<T: Clone> Snapshot<T> {
+    pub(crate) fn load(&self) -> Arc<T> {
+        self.0.read().unwrap().clone()
+    }
+
+    pub(crate) fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(Arc::make_mut(&mut self.0.write().unwrap()))
+    }
+}
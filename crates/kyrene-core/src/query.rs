@@ -1,25 +1,30 @@
-use std::{
-    future::Future,
-    marker::PhantomData,
-    pin::Pin,
-    task::{Context, Poll},
-};
+use std::{any::type_name, future::Future, marker::PhantomData, sync::Arc};
 
 use futures::{stream::FuturesUnordered, Stream, StreamExt};
 
 use crate::{
-    component::Mut,
+    change_detection::ChangeTick,
+    component::{Mut, MutInner, RefInner},
+    diagnostics::{track_lock, Access, HeldLock, LockInfo},
     entity::{Entity, EntitySet},
     handler::{EventHandlerMeta, HandlerParam},
+    lock::Read,
     prelude::{Component, Ref, WorldHandle},
+    storage::{Column, ColumnWrite},
 };
 
 pub struct QueryFilterState {
     entities_matching: EntitySet,
+    last_run: u64,
 }
 
 pub trait Queryable: Send + Sync {
     type Item: Send + Sync;
+    /// What [`iter`](Queryable::iter) borrows once up front, so it doesn't lock every entity's components one by one.
+    type Borrow: Send + Sync;
+
+    /// The components this query reads and writes.
+    fn meta() -> EventHandlerMeta;
 
     fn filter_state(
         world: &WorldHandle,
@@ -32,12 +37,75 @@ pub trait Queryable: Send + Sync {
         entity: Entity,
     ) -> impl Future<Output = Option<Self::Item>> + Send;
 
-    fn iter(world: &WorldHandle, state: &QueryFilterState)
-        -> impl Stream<Item = Self::Item> + Send;
+    fn borrow(world: &WorldHandle) -> impl Future<Output = Self::Borrow> + Send;
+
+    /// Like [`get`](Queryable::get), but takes the item out of what [`borrow`](Queryable::borrow) returned.
+    fn get_borrowed(
+        world: &WorldHandle,
+        state: &QueryFilterState,
+        borrow: &Self::Borrow,
+        entity: Entity,
+    ) -> impl Future<Output = Option<Self::Item>> + Send;
+
+    fn iter(
+        world: &WorldHandle,
+        state: &QueryFilterState,
+    ) -> impl Stream<Item = Self::Item> + Send {
+        // boxed so callers can poll it without pinning it first
+        Box::pin(
+            futures::stream::once(async move {
+                let borrow = Arc::new(Self::borrow(world).await);
+                let futs = FuturesUnordered::new();
+                for entity in state.entities_matching.iter() {
+                    let borrow = borrow.clone();
+                    futs.push(
+                        async move { Self::get_borrowed(world, state, &borrow, *entity).await },
+                    );
+                }
+                futs.filter_map(futures::future::ready)
+            })
+            .flatten()
+            .fuse(),
+        )
+    }
+}
+
+/// A column that [`Queryable::iter`] read-borrowed for the whole iteration, if the component is stored in one.
+pub struct ColumnRef(Option<ColumnRead>);
+
+struct ColumnRead {
+    column: Arc<Read<Column>>,
+    held: Option<Arc<HeldLock>>,
+}
+
+/// A column that [`Queryable::iter`] write-borrowed for the whole iteration, if the component is stored in one.
+pub struct ColumnMut(Option<(Arc<ColumnWrite>, ChangeTick)>);
+
+/// Implements the parts of [`Queryable`] that don't borrow anything up front.
+macro_rules! no_borrow {
+    () => {
+        type Borrow = ();
+
+        async fn borrow(_world: &WorldHandle) {}
+
+        async fn get_borrowed(
+            world: &WorldHandle,
+            state: &QueryFilterState,
+            _borrow: &(),
+            entity: Entity,
+        ) -> Option<Self::Item> {
+            Self::get(world, state, entity).await
+        }
+    };
 }
 
 impl Queryable for Entity {
     type Item = Entity;
+    no_borrow!();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
 
     async fn filter_state(_world: &WorldHandle, _state: &mut QueryFilterState) {}
 
@@ -48,17 +116,15 @@ impl Queryable for Entity {
     ) -> Option<Self::Item> {
         Some(entity)
     }
-
-    fn iter(
-        _world: &WorldHandle,
-        state: &QueryFilterState,
-    ) -> impl Stream<Item = Self::Item> + Send {
-        futures::stream::iter(state.entities_matching.iter().copied()).fuse()
-    }
 }
 
 impl<T: Component> Queryable for &T {
     type Item = Ref<T>;
+    type Borrow = ColumnRef;
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default().component::<T>()
+    }
 
     async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
         let old_entities = state.entities_matching.clone();
@@ -75,26 +141,54 @@ impl<T: Component> Queryable for &T {
         entity: Entity,
     ) -> Option<Self::Item> {
         if state.entities_matching.contains(&entity) {
-            world.get::<T>(entity).await
+            world.get_since::<T>(entity, state.last_run).await
         } else {
             None
         }
     }
 
-    fn iter(
+    async fn borrow(world: &WorldHandle) -> ColumnRef {
+        let Some(column) = world.column::<T>().await else {
+            return ColumnRef(None);
+        };
+        // borrow the whole column once and hand out refs into it
+        let info = LockInfo::component(Arc::as_ptr(&column), type_name::<T>(), None);
+        let (column, held) = track_lock(info, Access::Read, column.read_owned()).await;
+        ColumnRef(Some(ColumnRead {
+            column: Arc::new(column),
+            held: held.map(Arc::new),
+        }))
+    }
+
+    async fn get_borrowed(
         world: &WorldHandle,
         state: &QueryFilterState,
-    ) -> impl Stream<Item = Self::Item> + Send {
-        let futs = FuturesUnordered::new();
-        for entity in state.entities_matching.iter() {
-            futs.push(async move { world.get::<T>(*entity).await.unwrap() });
+        borrow: &ColumnRef,
+        entity: Entity,
+    ) -> Option<Self::Item> {
+        if !state.entities_matching.contains(&entity) {
+            return None;
+        }
+        match &borrow.0 {
+            Some(ColumnRead { column, held }) => {
+                let index = column.index_of(entity)?;
+                Some(
+                    Ref::<T>::new(RefInner::Column(column.clone(), index), state.last_run)
+                        .with_held(held.clone()),
+                )
+            }
+            None => world.get_since::<T>(entity, state.last_run).await,
         }
-        futs.fuse()
     }
 }
 
 impl<T: Component> Queryable for &mut T {
     type Item = Mut<T>;
+    type Borrow = ColumnMut;
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default().component_mut::<T>()
+    }
 
     async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
         let entities_with_component = world.entities_with::<T>().await;
@@ -109,69 +203,337 @@ impl<T: Component> Queryable for &mut T {
         entity: Entity,
     ) -> Option<Self::Item> {
         if state.entities_matching.contains(&entity) {
-            world.get_mut::<T>(entity).await
+            world.get_mut_since::<T>(entity, state.last_run).await
         } else {
             None
         }
     }
 
-    fn iter(
+    async fn borrow(world: &WorldHandle) -> ColumnMut {
+        let Some(column) = world.column::<T>().await else {
+            return ColumnMut(None);
+        };
+        let change_tick = world.change_tick().await;
+        // borrow the whole column once and lend each entity's component out of it
+        let info = LockInfo::component(Arc::as_ptr(&column), type_name::<T>(), None);
+        let (column, held) = track_lock(info, Access::Write, column.write_owned()).await;
+        ColumnMut(Some((
+            Arc::new(ColumnWrite::new::<T>(column, held)),
+            change_tick,
+        )))
+    }
+
+    async fn get_borrowed(
         world: &WorldHandle,
         state: &QueryFilterState,
-    ) -> impl Stream<Item = Self::Item> + Send {
-        let futs = FuturesUnordered::new();
-        for entity in state.entities_matching.iter() {
-            futs.push(async move { world.get_mut::<T>(*entity).await.unwrap() });
+        borrow: &ColumnMut,
+        entity: Entity,
+    ) -> Option<Self::Item> {
+        if !state.entities_matching.contains(&entity) {
+            return None;
+        }
+        match &borrow.0 {
+            Some((column, change_tick)) => {
+                let index = column.index_of(entity)?;
+                column.lend(index).then(|| {
+                    Mut::<T>::new(
+                        MutInner::Lent(column.clone(), index),
+                        change_tick.clone(),
+                        state.last_run,
+                    )
+                })
+            }
+            None => world.get_mut_since::<T>(entity, state.last_run).await,
         }
-        futs.fuse()
     }
 }
 
-pub struct ZipStream<T> {
-    zip: T,
+impl Queryable for () {
+    type Item = ();
+    no_borrow!();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
+
+    async fn filter_state(_world: &WorldHandle, _state: &mut QueryFilterState) {}
+
+    async fn get(_world: &WorldHandle, state: &QueryFilterState, entity: Entity) -> Option<()> {
+        state.entities_matching.contains(&entity).then_some(())
+    }
 }
 
-macro_rules! impl_zip_stream_tuple {
-    ($($name:ident),*) => {
-        #[allow(non_snake_case)]
-        impl<$($name: Stream + Unpin),*> Stream for ZipStream<($($name,)*)> {
-            type Item = ($($name::Item,)*);
+impl<T: Component> Queryable for Option<&T> {
+    type Item = Option<Ref<T>>;
+    type Borrow = ColumnRef;
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default().component::<T>()
+    }
+
+    async fn filter_state(_world: &WorldHandle, _state: &mut QueryFilterState) {}
+
+    async fn get(
+        world: &WorldHandle,
+        state: &QueryFilterState,
+        entity: Entity,
+    ) -> Option<Self::Item> {
+        if state.entities_matching.contains(&entity) {
+            Some(world.get_since::<T>(entity, state.last_run).await)
+        } else {
+            None
+        }
+    }
+
+    async fn borrow(world: &WorldHandle) -> ColumnRef {
+        <&T>::borrow(world).await
+    }
+
+    async fn get_borrowed(
+        world: &WorldHandle,
+        state: &QueryFilterState,
+        borrow: &ColumnRef,
+        entity: Entity,
+    ) -> Option<Self::Item> {
+        if state.entities_matching.contains(&entity) {
+            Some(<&T>::get_borrowed(world, state, borrow, entity).await)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Component> Queryable for Option<&mut T> {
+    type Item = Option<Mut<T>>;
+    type Borrow = ColumnMut;
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default().component_mut::<T>()
+    }
+
+    async fn filter_state(_world: &WorldHandle, _state: &mut QueryFilterState) {}
+
+    async fn get(
+        world: &WorldHandle,
+        state: &QueryFilterState,
+        entity: Entity,
+    ) -> Option<Self::Item> {
+        if state.entities_matching.contains(&entity) {
+            Some(world.get_mut_since::<T>(entity, state.last_run).await)
+        } else {
+            None
+        }
+    }
+
+    async fn borrow(world: &WorldHandle) -> ColumnMut {
+        <&mut T>::borrow(world).await
+    }
+
+    async fn get_borrowed(
+        world: &WorldHandle,
+        state: &QueryFilterState,
+        borrow: &ColumnMut,
+        entity: Entity,
+    ) -> Option<Self::Item> {
+        if state.entities_matching.contains(&entity) {
+            Some(<&mut T>::get_borrowed(world, state, borrow, entity).await)
+        } else {
+            None
+        }
+    }
+}
+
+/// Only matches entities that have a `T`, without borrowing it.
+pub struct With<T: Component>(PhantomData<T>);
+
+impl<T: Component> Queryable for With<T> {
+    type Item = ();
+    no_borrow!();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
+
+    async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
+        let entities_with_component = world.entities_with::<T>().await;
+        state
+            .entities_matching
+            .retain(|e| entities_with_component.contains(e));
+    }
+
+    async fn get(world: &WorldHandle, state: &QueryFilterState, entity: Entity) -> Option<()> {
+        <()>::get(world, state, entity).await
+    }
+}
+
+/// Only matches entities that don't have a `T`.
+pub struct Without<T: Component>(PhantomData<T>);
+
+impl<T: Component> Queryable for Without<T> {
+    type Item = ();
+    no_borrow!();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
+
+    async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
+        let entities_with_component = world.entities_with::<T>().await;
+        state
+            .entities_matching
+            .retain(|e| !entities_with_component.contains(e));
+    }
+
+    async fn get(world: &WorldHandle, state: &QueryFilterState, entity: Entity) -> Option<()> {
+        <()>::get(world, state, entity).await
+    }
+}
+
+/// Yields whether the entity has a `T`, without filtering anything out.
+pub struct Has<T: Component>(PhantomData<T>);
+
+impl<T: Component> Queryable for Has<T> {
+    type Item = bool;
+    no_borrow!();
 
-            fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-                let ($(ref mut $name,)*) = self.zip;
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
 
+    async fn filter_state(_world: &WorldHandle, _state: &mut QueryFilterState) {}
+
+    async fn get(world: &WorldHandle, state: &QueryFilterState, entity: Entity) -> Option<bool> {
+        if state.entities_matching.contains(&entity) {
+            Some(world.has::<T>(entity).await)
+        } else {
+            None
+        }
+    }
+}
+
+/// Only matches entities whose `T` was added since the handler last ran.
+pub struct Added<T: Component>(PhantomData<T>);
+
+impl<T: Component> Queryable for Added<T> {
+    type Item = ();
+    no_borrow!();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
+
+    async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
+        let loans = world
+            .component_loans::<T>(state.entities_matching.iter().copied())
+            .await;
+
+        let mut entities_matching = EntitySet::default();
+        for (entity, loan) in loans {
+            if loan
+                .ticks(entity)
+                .await
+                .is_some_and(|ticks| ticks.is_added(state.last_run))
+            {
+                entities_matching.insert(entity);
+            }
+        }
+        state.entities_matching = entities_matching;
+    }
+
+    async fn get(world: &WorldHandle, state: &QueryFilterState, entity: Entity) -> Option<()> {
+        <()>::get(world, state, entity).await
+    }
+}
+
+/// Only matches entities whose `T` was added or mutably dereferenced since the handler last ran.
+pub struct Changed<T: Component>(PhantomData<T>);
+
+impl<T: Component> Queryable for Changed<T> {
+    type Item = ();
+    no_borrow!();
+
+    fn meta() -> EventHandlerMeta {
+        EventHandlerMeta::default()
+    }
+
+    async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
+        let loans = world
+            .component_loans::<T>(state.entities_matching.iter().copied())
+            .await;
+
+        let mut entities_matching = EntitySet::default();
+        for (entity, loan) in loans {
+            if loan
+                .ticks(entity)
+                .await
+                .is_some_and(|ticks| ticks.is_changed(state.last_run))
+            {
+                entities_matching.insert(entity);
+            }
+        }
+        state.entities_matching = entities_matching;
+    }
+
+    async fn get(world: &WorldHandle, state: &QueryFilterState, entity: Entity) -> Option<()> {
+        <()>::get(world, state, entity).await
+    }
+}
+
+/// Matches entities that match any of the filters in the tuple.
+pub struct Or<T>(PhantomData<T>);
+
+macro_rules! impl_or_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: Queryable),*> Queryable for Or<($($name,)*)> {
+            type Item = ();
+            no_borrow!();
+
+            fn meta() -> EventHandlerMeta {
+                let mut meta = EventHandlerMeta::default();
+                $(meta = meta + $name::meta();)*
+                meta
+            }
+
+            async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
+                let mut entities_matching = EntitySet::default();
                 $(
-                    let $name = match $name.poll_next_unpin(cx) {
-                        Poll::Ready(Some(elt)) => elt,
-                        Poll::Ready(None) => {
-                            return Poll::Ready(None);
-                        },
-                        Poll::Pending => {
-                            cx.waker().wake_by_ref();
-                            return Poll::Pending;
-                        },
+                    let mut inner = QueryFilterState {
+                        entities_matching: state.entities_matching.clone(),
+                        last_run: state.last_run,
                     };
+                    $name::filter_state(world, &mut inner).await;
+                    entities_matching.extend(inner.entities_matching);
                 )*
+                state.entities_matching = entities_matching;
+            }
 
-                Poll::Ready(Some(($($name,)*)))
+            async fn get(world: &WorldHandle, state: &QueryFilterState, entity: Entity) -> Option<()> {
+                <()>::get(world, state, entity).await
             }
         }
     };
 }
-impl_zip_stream_tuple!(A);
-impl_zip_stream_tuple!(A, B);
-impl_zip_stream_tuple!(A, B, C);
-impl_zip_stream_tuple!(A, B, C, D);
-impl_zip_stream_tuple!(A, B, C, D, E);
-impl_zip_stream_tuple!(A, B, C, D, E, F);
-impl_zip_stream_tuple!(A, B, C, D, E, F, G);
-impl_zip_stream_tuple!(A, B, C, D, E, F, G, H);
+impl_or_tuple!(A);
+impl_or_tuple!(A, B);
+impl_or_tuple!(A, B, C);
+impl_or_tuple!(A, B, C, D);
+impl_or_tuple!(A, B, C, D, E);
+impl_or_tuple!(A, B, C, D, E, F);
+impl_or_tuple!(A, B, C, D, E, F, G);
+impl_or_tuple!(A, B, C, D, E, F, G, H);
 
 macro_rules! impl_queryable_tuple {
     ($($name:ident),*) => {
         #[allow(non_snake_case)]
         impl<$($name: Queryable),*> Queryable for ($($name,)*) {
             type Item = ($($name::Item,)*);
+            type Borrow = ($($name::Borrow,)*);
+
+            fn meta() -> EventHandlerMeta {
+                let mut meta = EventHandlerMeta::default();
+                $(meta = meta + $name::meta();)*
+                meta
+            }
 
             async fn filter_state(world: &WorldHandle, state: &mut QueryFilterState) {
                 $($name::filter_state(world, state).await);*
@@ -187,10 +549,23 @@ macro_rules! impl_queryable_tuple {
                 )*))
             }
 
-            fn iter(world: &WorldHandle, state: &QueryFilterState) -> impl Stream<Item = Self::Item> + Send {
-                ZipStream { zip: ($(
-                    Box::pin($name::iter(world, state)),
-                )*)}.fuse()
+            async fn borrow(world: &WorldHandle) -> Self::Borrow {
+                // one element at a time, so the columns are always locked in the same order
+                ($($name::borrow(world).await,)*)
+            }
+
+            // every element of the tuple is fetched for the same entity before it's yielded,
+            // while the entities themselves still resolve concurrently in `iter`
+            async fn get_borrowed(
+                world: &WorldHandle,
+                state: &QueryFilterState,
+                borrow: &Self::Borrow,
+                entity: Entity,
+            ) -> Option<Self::Item> {
+                let ($($name,)*) = borrow;
+                Some(($(
+                    $name::get_borrowed(world, state, $name, entity).await?,
+                )*))
             }
         }
     };
@@ -204,19 +579,26 @@ impl_queryable_tuple!(A, B, C, D, E, F);
 impl_queryable_tuple!(A, B, C, D, E, F, G);
 impl_queryable_tuple!(A, B, C, D, E, F, G, H);
 
-pub struct Query<Q: Queryable> {
+pub struct Query<Q: Queryable, F: Queryable = ()> {
     state: QueryFilterState,
     world: WorldHandle,
-    _marker: PhantomData<Q>,
+    _marker: PhantomData<(Q, F)>,
 }
 
-impl<Q: Queryable> Query<Q> {
+impl<Q: Queryable, F: Queryable> Query<Q, F> {
     pub async fn new(world: WorldHandle) -> Self {
+        Self::new_since(world, 0).await
+    }
+
+    /// Creates a query whose change detection is relative to the `last_run` change tick.
+    pub async fn new_since(world: WorldHandle, last_run: u64) -> Self {
         let mut state = QueryFilterState {
             entities_matching: world.all_entities().await,
+            last_run,
         };
 
         Q::filter_state(&world, &mut state).await;
+        F::filter_state(&world, &mut state).await;
 
         Self {
             state,
@@ -225,30 +607,40 @@ impl<Q: Queryable> Query<Q> {
         }
     }
 
+    pub fn last_run(&self) -> u64 {
+        self.state.last_run
+    }
+
     pub async fn get(&self, entity: Entity) -> Option<Q::Item> {
         Q::get(&self.world, &self.state, entity).await
     }
 
-    pub fn iter(&self) -> impl Stream<Item = Q::Item> + use<'_, Q> {
+    pub fn iter(&self) -> impl Stream<Item = Q::Item> + use<'_, Q, F> {
         Q::iter(&self.world, &self.state)
     }
 }
 
-impl<Q: Queryable> HandlerParam for Query<Q> {
-    type Item = Query<Q>;
-    type State = ();
+impl<Q: Queryable, F: Queryable> HandlerParam for Query<Q, F> {
+    type Item = Query<Q, F>;
+    /// The change tick this query was last fetched at.
+    type State = u64;
 
     fn meta() -> EventHandlerMeta {
-        EventHandlerMeta::default()
+        Q::meta() + F::meta()
     }
 
-    async fn init_state(_world: WorldHandle) -> Self::State {}
+    async fn init_state(_world: WorldHandle) -> Self::State {
+        0
+    }
 
-    async fn fetch(world: WorldHandle, _: &mut ()) -> Self::Item {
-        world.query::<Q>().await
+    async fn fetch(world: WorldHandle, last_run: &mut u64) -> Option<Self::Item> {
+        let this_run = world.change_tick().await.increment();
+        let query = Query::new_since(world, *last_run).await;
+        *last_run = this_run;
+        Some(query)
     }
 
-    async fn can_run(_world: WorldHandle, _: &()) -> bool {
+    async fn can_run(_world: WorldHandle, _: &u64) -> bool {
         true
     }
 }
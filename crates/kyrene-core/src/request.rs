@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use crate::{
+    component::Component,
+    error::{HandlerError, HandlerResult, IntoHandlerResult},
+    event::EventContext,
+    world_handle::WorldHandle,
+};
+
+/// An event that its handlers can answer, fired with [`WorldHandle::request`].
+///
+/// Handlers answer by returning a [`Response`], or an `Option` or `Result` of one.
+pub trait Request: Component {
+    type Response: Component;
+}
+
+/// Returned from a handler of a [`Request`] to answer it.
+///
+/// Only a [`Response`] of the request's own [`Request::Response`] type can be returned. If the request
+/// is fired with [`WorldHandle::fire_event`] rather than [`WorldHandle::request`], it's dropped.
+///
+/// ```compile_fail
+/// # use kyrene_core::prelude::*;
+/// struct Ping;
+///
+/// impl Request for Ping {
+///     type Response = u32;
+/// }
+///
+/// async fn wrong_type(_event: Event<Ping>) -> Response<&'static str> {
+///     Response("pong")
+/// }
+///
+/// World::new().add_event_handler(wrong_type);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Response<T: Component>(pub T);
+
+/// Collects the [`Response`]s to a single [`WorldHandle::request`].
+#[derive(Default)]
+pub struct Responses {
+    responses: std::sync::Mutex<Vec<Box<dyn Component>>>,
+}
+
+impl Responses {
+    fn push<T: Component>(&self, response: T) {
+        self.responses.lock().unwrap().push(Box::new(response));
+    }
+
+    fn take<T: Component>(&self) -> Vec<T> {
+        std::mem::take(&mut *self.responses.lock().unwrap())
+            .into_iter()
+            // handlers of a request can only return its own response type
+            .map(|response| *response.downcast::<T>().unwrap_or_else(|_| unreachable!()))
+            .collect()
+    }
+}
+
+impl<Q: Request> IntoHandlerResult<Q> for Response<Q::Response> {
+    fn into_handler_result(self) -> HandlerResult {
+        Ok(())
+    }
+
+    fn into_handler_result_with(self, responses: Option<&Responses>) -> HandlerResult {
+        if let Some(responses) = responses {
+            responses.push(self.0);
+        }
+        Ok(())
+    }
+}
+
+impl<Q: Request> IntoHandlerResult<Q> for Option<Response<Q::Response>> {
+    fn into_handler_result(self) -> HandlerResult {
+        Ok(())
+    }
+
+    fn into_handler_result_with(self, responses: Option<&Responses>) -> HandlerResult {
+        match self {
+            Some(response) => IntoHandlerResult::<Q>::into_handler_result_with(response, responses),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<Q, E> IntoHandlerResult<Q> for Result<Response<Q::Response>, E>
+where
+    Q: Request,
+    E: Into<HandlerError> + Send + Sync + 'static,
+{
+    fn into_handler_result(self) -> HandlerResult {
+        self.map(|_| ()).map_err(Into::into)
+    }
+
+    fn into_handler_result_with(self, responses: Option<&Responses>) -> HandlerResult {
+        let response = self.map_err(Into::into)?;
+        IntoHandlerResult::<Q>::into_handler_result_with(response, responses)
+    }
+}
+
+impl WorldHandle {
+    /// Fires `query`, waits for its handlers, and returns every [`Response`] they gave.
+    ///
+    /// Handlers that don't have to run one after another run concurrently, so the responses are in the
+    /// order the handlers finished, not the order they were added or ordered in. Handlers that failed or
+    /// returned `None` don't contribute one, and nothing is returned if the request has no handlers.
+    pub async fn request<Q: Request>(&self, query: Q) -> Vec<Q::Response> {
+        let Some(dispatcher) = self.world.read().await.get_event::<Q>() else {
+            return Vec::new();
+        };
+        let dispatcher = dispatcher.into_dyn();
+        let responses = Arc::new(Responses::default());
+        let context = EventContext {
+            propagation: None,
+            responses: Some(responses.clone()),
+        };
+        dispatcher
+            .fire_dyn(self.clone(), Arc::new(query), context, true)
+            .await;
+        responses.take()
+    }
+
+    /// Like [`WorldHandle::request`], but only returns the first [`Response`] given, which is from
+    /// whichever handler finished first.
+    ///
+    /// Every handler still runs.
+    pub async fn request_first<Q: Request>(&self, query: Q) -> Option<Q::Response> {
+        self.request(query).await.into_iter().next()
+    }
+}
@@ -1,27 +1,139 @@
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    any::type_name, fmt::Debug, future::Future, marker::PhantomData, sync::Arc, time::Duration,
+};
+
+use tokio::sync::Notify;
 
 use crate::{
-    component::{DynComponent, Mut},
+    change_detection::{ChangeTick, ComponentTicks},
+    component::{DynComponent, Mut, MutInner, RefInner},
+    diagnostics::{track_lock, Access, LockInfo},
     lock::RwLock,
     prelude::{Component, Ref},
     util::{TypeIdMap, TypeInfo},
 };
 
+/// Type-erased constructor for one of a resource's lifecycle events.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ResourceEvent {
+    pub(crate) type_id: TypeInfo,
+    pub(crate) new: fn() -> Arc<dyn Component>,
+}
+
+impl ResourceEvent {
+    fn of<E: Component + Default>() -> Self {
+        Self {
+            type_id: TypeInfo::of::<E>(),
+            new: || Arc::new(E::default()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ResourceInfo {
+    pub(crate) on_insert: ResourceEvent,
+    pub(crate) on_remove: ResourceEvent,
+    pub(crate) on_change: ResourceEvent,
+}
+
+impl ResourceInfo {
+    pub(crate) fn of<T: Component>() -> Self {
+        Self {
+            on_insert: ResourceEvent::of::<ResourceInserted<T>>(),
+            on_remove: ResourceEvent::of::<ResourceRemoved<T>>(),
+            on_change: ResourceEvent::of::<ResourceChanged<T>>(),
+        }
+    }
+}
+
+macro_rules! define_resource_events {
+    ($($(#[$attr:meta])* $name:ident),*) => {
+        $(
+            $(#[$attr])*
+            pub struct $name<T: Component>(PhantomData<T>);
+
+            impl<T: Component> Default for $name<T> {
+                fn default() -> Self {
+                    Self(PhantomData)
+                }
+            }
+
+            impl<T: Component> Clone for $name<T> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<T: Component> Copy for $name<T> {}
+
+            impl<T: Component> Debug for $name<T> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "{}<{}>", stringify!($name), type_name::<T>())
+                }
+            }
+        )*
+    };
+}
+
+define_resource_events!(
+    /// Fired every time a `T` resource is inserted, including when it replaces an existing one.
+    ResourceInserted,
+    /// Fired after the `T` resource was removed.
+    ResourceRemoved,
+    /// Fired once per [`WorldTick`](crate::world::WorldTick) if the `T` resource was mutably dereferenced since the last one.
+    ///
+    /// Writes are batched rather than reported as they happen: however many times `T` is written during a tick,
+    /// this fires once, before the next tick's [`WorldTick`](crate::world::WorldTick) handlers run, or when
+    /// [`WorldHandle::flush_resource_changes`](crate::world_handle::WorldHandle::flush_resource_changes) is called.
+    ResourceChanged
+);
+
+struct ResourceStorage {
+    info: ResourceInfo,
+    loan: Arc<RwLock<Option<DynComponent>>>,
+    ticks: Arc<ComponentTicks>,
+    /// Counts up with every insert, for dropping resources in reverse.
+    insertion: u64,
+}
+
 #[derive(Default)]
 pub struct Resources {
-    map: TypeIdMap<Arc<RwLock<Option<DynComponent>>>>,
+    map: TypeIdMap<ResourceStorage>,
+    inserted: std::sync::Mutex<TypeIdMap<Arc<Notify>>>,
+    change_tick: ChangeTick,
+    last_change_sweep: u64,
+    next_insertion: u64,
 }
 
 impl Resources {
+    pub fn new(change_tick: ChangeTick) -> Self {
+        Self {
+            change_tick,
+            ..Default::default()
+        }
+    }
+
     pub async fn insert<T: Component>(&mut self, resource: T) -> Option<T> {
         let component_type_id = TypeInfo::of::<T>();
 
         let old = self.map.insert(
             component_type_id,
-            Arc::new(RwLock::new(Some(DynComponent::new(resource)))),
-        )?;
+            ResourceStorage {
+                info: ResourceInfo::of::<T>(),
+                loan: Arc::new(RwLock::new(Some(DynComponent::new(resource)))),
+                ticks: Arc::new(ComponentTicks::new(self.change_tick.increment())),
+                insertion: self.next_insertion,
+            },
+        );
+        self.next_insertion += 1;
 
-        let old = old.write().await.take().unwrap();
+        if let Some(inserted) = self.inserted.get_mut().unwrap().get(&component_type_id) {
+            inserted.notify_waiters();
+        }
+
+        let old = old?;
+
+        let old = old.loan.write().await.take().unwrap();
         let old: T = *old.component.downcast().unwrap_or_else(|_| unreachable!());
         Some(old)
     }
@@ -31,7 +143,7 @@ impl Resources {
 
         let component = self.map.remove(&component_type_id)?;
 
-        let component = component.write().await.take().unwrap();
+        let component = component.loan.write().await.take().unwrap();
         let component: T = *component
             .component
             .downcast()
@@ -39,11 +151,42 @@ impl Resources {
         Some(component)
     }
 
+    /// Drops every resource, most recently inserted first, so later resources can still rely on the ones they were built from.
+    pub async fn clear(&mut self) {
+        let mut storages: Vec<_> = self.map.drain().map(|(_, storage)| storage).collect();
+        storages.sort_by_key(|storage| std::cmp::Reverse(storage.insertion));
+
+        for storage in storages {
+            drop(storage.loan.write().await.take());
+        }
+    }
+
     pub fn contains<T: Component>(&self) -> bool {
         let component_type_id = TypeInfo::of::<T>();
         self.map.contains_key(&component_type_id)
     }
 
+    pub fn ticks<T: Component>(&self) -> Option<ComponentTicks> {
+        let component = self.map.get(&TypeInfo::of::<T>())?;
+        Some((*component.ticks).clone())
+    }
+
+    /// Returns the resources that were mutably dereferenced since the last call.
+    pub(crate) fn take_changed(&mut self) -> Vec<ResourceInfo> {
+        let last_sweep = self.last_change_sweep;
+        self.last_change_sweep = self.change_tick.get();
+
+        self.map
+            .values()
+            // inserting sets the changed tick too, but that has its own event
+            .filter(|storage| {
+                storage.ticks.is_changed(last_sweep)
+                    && storage.ticks.changed() != storage.ticks.added()
+            })
+            .map(|storage| storage.info)
+            .collect()
+    }
+
     pub(crate) fn contains_dyn(&self, resource_type_id: TypeInfo) -> bool {
         self.map.contains_key(&resource_type_id)
     }
@@ -52,63 +195,98 @@ impl Resources {
         let component_type_id = TypeInfo::of::<T>();
 
         let component = self.map.get(&component_type_id)?;
-        let inner = component.clone().read_owned().await;
+        let info = LockInfo::resource(Arc::as_ptr(&component.loan), type_name::<T>());
+        let (inner, held) =
+            track_lock(info, Access::Read, component.loan.clone().read_owned()).await;
 
-        Some(Ref {
-            inner,
-            _marker: PhantomData,
-        })
+        Some(
+            Ref::<T>::new(RefInner::Single(inner, component.ticks.clone()), 0)
+                .with_held(held.map(Arc::new)),
+        )
     }
 
     pub async fn get_mut<T: Component>(&self) -> Option<Mut<T>> {
         let component_type_id = TypeInfo::of::<T>();
 
         let component = self.map.get(&component_type_id)?;
-        let inner = component.clone().write_owned().await;
+        let info = LockInfo::resource(Arc::as_ptr(&component.loan), type_name::<T>());
+        let (inner, held) =
+            track_lock(info, Access::Write, component.loan.clone().write_owned()).await;
 
-        Some(Mut {
-            inner,
-            _marker: PhantomData,
-        })
+        Some(
+            Mut::<T>::new(
+                MutInner::Single(inner, component.ticks.clone()),
+                self.change_tick.clone(),
+                0,
+            )
+            .with_held(held),
+        )
     }
 
-    pub async fn wait_for<T: Component>(&mut self) -> Ref<T> {
-        let mut start = tokio::time::Instant::now();
-
-        loop {
-            if let Some(res) = self.get::<T>().await {
-                return res;
-            }
-
-            tokio::task::yield_now().await;
+    /// Returns the notification signalled every time a `T` is inserted.
+    pub(crate) fn inserted<T: Component>(&self) -> Arc<Notify> {
+        self.inserted
+            .lock()
+            .unwrap()
+            .entry(TypeInfo::of::<T>())
+            .or_default()
+            .clone()
+    }
 
-            if start.elapsed() >= tokio::time::Duration::from_secs(5) {
-                tracing::warn!(
-                    "Waiting a long time for resource ref {}...",
-                    std::any::type_name::<T>()
-                );
-                start = tokio::time::Instant::now();
-            }
-        }
+    /// Waits until a `T` is inserted, if there isn't one already.
+    pub async fn wait_for<T: Component>(&self) -> Ref<T> {
+        wait_for_insert(|| async { self.inserted::<T>() }, || self.get::<T>()).await
     }
 
-    pub async fn wait_for_mut<T: Component>(&mut self) -> Mut<T> {
-        let mut start = tokio::time::Instant::now();
+    /// Waits until a `T` is inserted, if there isn't one already.
+    pub async fn wait_for_mut<T: Component>(&self) -> Mut<T> {
+        wait_for_insert(|| async { self.inserted::<T>() }, || self.get_mut::<T>()).await
+    }
+}
 
-        loop {
-            if let Some(res) = self.get_mut::<T>().await {
-                return res;
-            }
+/// Returned when a resource didn't show up before the timeout passed to
+/// [`WorldHandle::await_resource`](crate::world_handle::WorldHandle::await_resource) elapsed.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("timed out after {timeout:?} waiting for resource `{type_name}`")]
+pub struct ResourceTimeout {
+    pub type_name: &'static str,
+    pub timeout: Duration,
+}
 
-            tokio::task::yield_now().await;
+/// Calls `get` until it returns a resource, waiting on the notification returned by `inserted` between tries.
+pub(crate) async fn wait_for_insert<R, N, G>(inserted: impl Fn() -> N, get: impl Fn() -> G) -> R
+where
+    N: Future<Output = Arc<Notify>>,
+    G: Future<Output = Option<R>>,
+{
+    loop {
+        let inserted = inserted().await;
+        let notified = inserted.notified();
+        tokio::pin!(notified);
+        // register before checking, so an insert in between isn't missed
+        notified.as_mut().enable();
 
-            if start.elapsed() >= tokio::time::Duration::from_secs(5) {
-                tracing::warn!(
-                    "Waiting a long time for resource mut {}...",
-                    std::any::type_name::<T>()
-                );
-                start = tokio::time::Instant::now();
-            }
+        if let Some(res) = get().await {
+            return res;
         }
+
+        notified.await;
     }
 }
+
+/// Runs `fut` to completion, or until `timeout` elapses if there is one.
+pub(crate) async fn with_timeout<T: Component, R>(
+    timeout: Option<Duration>,
+    fut: impl Future<Output = R>,
+) -> Result<R, ResourceTimeout> {
+    let Some(timeout) = timeout else {
+        return Ok(fut.await);
+    };
+
+    tokio::time::timeout(timeout, fut)
+        .await
+        .map_err(|_| ResourceTimeout {
+            type_name: type_name::<T>(),
+            timeout,
+        })
+}
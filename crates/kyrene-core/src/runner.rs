@@ -0,0 +1,351 @@
+use std::{
+    future::Future,
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::{
+    task::JoinSet,
+    time::{Interval, MissedTickBehavior},
+};
+
+use crate::{
+    event::Event,
+    util::SyncBoxFuture,
+    world::{World, WorldShutdown, WorldStartup, WorldTick},
+    world_handle::WorldHandle,
+};
+
+/// How long shutdown waits for detached handlers before giving up on them.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Ticks per second for [`World::run`].
+pub const DEFAULT_TICK_RATE: f64 = 60.0;
+
+/// Fire this to stop the world after the current tick.
+///
+/// If it's fired more than once, the first one wins.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AppExit {
+    #[default]
+    Success,
+    /// Exits with the given code. `Error(0)` is still a failure, and exits with 1.
+    Error(u8),
+}
+
+impl AppExit {
+    pub fn is_success(&self) -> bool {
+        matches!(self, AppExit::Success)
+    }
+
+    pub fn code(&self) -> u8 {
+        match self {
+            AppExit::Success => 0,
+            AppExit::Error(code) => (*code).max(1),
+        }
+    }
+}
+
+impl From<AppExit> for ExitCode {
+    fn from(exit: AppExit) -> Self {
+        ExitCode::from(exit.code())
+    }
+}
+
+/// Bookkeeping shared between a runner and everything running on its world.
+#[derive(Default)]
+pub(crate) struct RunState {
+    exit: std::sync::Mutex<Option<AppExit>>,
+    exit_handler_added: AtomicBool,
+    /// Set by [`WorldHandle::startup`] and cleared by [`WorldHandle::shutdown`].
+    started: AtomicBool,
+    detached: std::sync::Mutex<Vec<JoinSet<()>>>,
+}
+
+impl RunState {
+    pub(crate) fn exit_requested(&self) -> Option<AppExit> {
+        *self.exit.lock().unwrap()
+    }
+
+    fn is_started(&self) -> bool {
+        self.started.load(Ordering::Acquire)
+    }
+
+    fn request_exit(&self, exit: AppExit) {
+        self.exit.lock().unwrap().get_or_insert(exit);
+    }
+
+    /// Keeps track of handlers that were fired without being awaited, so shutdown can wait for them.
+    pub(crate) fn detach(&self, handlers: JoinSet<()>) {
+        let mut detached = self.detached.lock().unwrap();
+        detached.retain_mut(|handlers| {
+            while handlers.try_join_next().is_some() {}
+            !handlers.is_empty()
+        });
+        detached.push(handlers);
+    }
+
+    fn take_detached(&self) -> Vec<JoinSet<()>> {
+        std::mem::take(&mut *self.detached.lock().unwrap())
+    }
+}
+
+async fn record_exit(event: Event<AppExit>, world: WorldHandle) {
+    let run_state = world.world.read().await.run_state();
+    run_state.request_exit(**event);
+}
+
+type RunCondition = Arc<dyn Fn(WorldHandle) -> SyncBoxFuture<'static, bool> + Send + Sync>;
+
+/// Controls how long [`World::run_headless`] runs for and how fast it ticks.
+#[derive(Clone)]
+pub struct RunConfig {
+    /// Stop after this many ticks.
+    pub max_ticks: Option<u64>,
+    /// Ticks per second. Ticks run back-to-back if `None`.
+    pub tick_rate: Option<f64>,
+    /// Checked after every tick, stopping once it returns `true`.
+    pub until: Option<RunCondition>,
+    /// How long to wait for detached handlers once stopped.
+    pub shutdown_timeout: Duration,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            max_ticks: None,
+            tick_rate: None,
+            until: None,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+        }
+    }
+}
+
+impl RunConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_ticks(mut self, max_ticks: u64) -> Self {
+        self.max_ticks = Some(max_ticks);
+        self
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `tick_rate` is not finite and positive.
+    pub fn with_tick_rate(mut self, tick_rate: f64) -> Self {
+        assert_tick_rate(tick_rate);
+        self.tick_rate = Some(tick_rate);
+        self
+    }
+
+    pub fn until<F, Fut>(mut self, condition: F) -> Self
+    where
+        F: Fn(WorldHandle) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + Sync + 'static,
+    {
+        self.until = Some(Arc::new(move |world| Box::pin(condition(world))));
+        self
+    }
+
+    pub fn with_shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.shutdown_timeout = shutdown_timeout;
+        self
+    }
+}
+
+/// Returned when a [`WorldHandle`] is still alive after a headless run, e.g. one kept in a resource,
+/// so the [`World`] can't be handed back.
+///
+/// The world isn't lost: it's still reachable through the handle kept here.
+#[derive(thiserror::Error)]
+#[error("a WorldHandle outlived the headless run")]
+pub struct WorldStillShared(pub WorldHandle);
+
+impl std::fmt::Debug for WorldStillShared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WorldStillShared").finish_non_exhaustive()
+    }
+}
+
+impl World {
+    /// Runs the world without a window or a global tracing subscriber, then hands it back.
+    ///
+    /// Fires [`WorldStartup`], ticks until the [`RunConfig`] says to stop or an [`AppExit`] is fired,
+    /// and then shuts down. Resources are left in place so they can be inspected afterwards.
+    /// With neither [`RunConfig::max_ticks`] nor [`RunConfig::until`] set, this only returns on [`AppExit`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`RunConfig::tick_rate`] is not finite and positive.
+    pub fn run_headless(self, config: RunConfig) -> Result<World, WorldStillShared> {
+        if let Some(tick_rate) = config.tick_rate {
+            assert_tick_rate(tick_rate);
+        }
+
+        run_on_runtime(self, |world| async move {
+            world.startup().await;
+
+            let mut interval = config.tick_rate.map(tick_interval);
+
+            let mut ticks = 0;
+            while config.max_ticks.is_none_or(|max_ticks| ticks < max_ticks) {
+                if let Some(interval) = &mut interval {
+                    interval.tick().await;
+                }
+
+                world.update().await;
+                ticks += 1;
+
+                if world.exit_requested().await.is_some() {
+                    break;
+                }
+                if let Some(until) = &config.until {
+                    if until(world.clone()).await {
+                        break;
+                    }
+                }
+            }
+
+            world.shutdown(config.shutdown_timeout).await;
+        })
+    }
+
+    /// Runs a single tick, firing [`WorldStartup`] first if the world hasn't been started yet.
+    ///
+    /// Each call gets a runtime of its own, so anything a handler left running in the background is
+    /// cancelled when it returns. If a [`WorldHandle`] outlives that, `self` is left as a new [`World`]
+    /// and the world is reachable through the handle in the error instead.
+    pub fn update(&mut self) -> Result<(), WorldStillShared> {
+        let world = std::mem::take(self);
+        *self = run_on_runtime(world, |world| async move {
+            if !world.world.read().await.run_state().is_started() {
+                world.startup().await;
+            }
+            world.update().await;
+        })?;
+        Ok(())
+    }
+
+    /// The [`AppExit`] fired during the last run, if any.
+    pub fn exit_requested(&self) -> Option<AppExit> {
+        self.run_state().exit_requested()
+    }
+}
+
+/// Paces ticks to `tick_rate` per second. A late tick pushes the next ones back rather than bursting to catch up.
+pub(crate) fn tick_interval(tick_rate: f64) -> Interval {
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / tick_rate));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+/// Runs `f` on a runtime of its own, then takes the world back out of its handle.
+fn run_on_runtime<F, Fut>(world: World, f: F) -> Result<World, WorldStillShared>
+where
+    F: FnOnce(WorldHandle) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let world = world.into_world_handle();
+    runtime.block_on(f(world.clone()));
+
+    // dropping the runtime cancels anything still running, along with the handles it was holding
+    drop(runtime);
+
+    match Arc::try_unwrap(world.world) {
+        Ok(world) => Ok(world.into_inner()),
+        Err(world) => Err(WorldStillShared(WorldHandle { world })),
+    }
+}
+
+fn assert_tick_rate(tick_rate: f64) {
+    assert!(
+        tick_rate.is_finite() && tick_rate > 0.0,
+        "tick rate must be finite and positive, got {tick_rate}"
+    );
+}
+
+impl WorldHandle {
+    /// Fires any events queued while building the world, then [`WorldStartup`].
+    pub async fn startup(&self) {
+        let run_state = self.world.read().await.run_state();
+        *run_state.exit.lock().unwrap() = None;
+        run_state.started.store(true, Ordering::Release);
+
+        if !run_state.exit_handler_added.swap(true, Ordering::AcqRel) {
+            let dispatcher = self.world.read().await.get_event::<AppExit>().unwrap();
+            dispatcher.add_handler(record_exit);
+        }
+
+        self.flush_pending_events().await;
+        self.fire_event(WorldStartup, true).await;
+    }
+
+    /// Runs a single tick: advances [`Time`](crate::time::Time), fires any due fixed updates, timers and
+    /// resource changes, swaps every [`EventQueue`](crate::event_queue::EventQueue), then fires
+    /// [`WorldTick`] and waits for its handlers.
+    pub async fn update(&self) {
+        let tick = self.world.write().await.advance_tick();
+        // anything deferred outside of a handler batch
+        self.apply_commands().await;
+        self.advance_time().await;
+        self.advance_timers().await;
+        self.flush_resource_changes().await;
+        self.world.read().await.swap_event_queues().await;
+        self.fire_event(WorldTick { tick }, true).await;
+    }
+
+    /// The [`AppExit`] fired since [`WorldHandle::startup`], if any.
+    pub async fn exit_requested(&self) -> Option<AppExit> {
+        self.world.read().await.exit_requested()
+    }
+
+    /// Fires [`WorldShutdown`], then waits up to `timeout` for any handlers that were fired without
+    /// being awaited, aborting whatever is still running after that.
+    ///
+    /// Returns the [`AppExit`] that stopped the world, or [`AppExit::Success`] if it was stopped some other way.
+    pub async fn shutdown(&self, timeout: Duration) -> AppExit {
+        self.fire_event(WorldShutdown, true).await;
+
+        let run_state = self.world.read().await.run_state();
+        run_state.started.store(false, Ordering::Release);
+        let drained = tokio::time::timeout(timeout, async {
+            // detached handlers can detach more handlers of their own
+            loop {
+                let detached = run_state.take_detached();
+                if detached.is_empty() {
+                    break;
+                }
+                for handlers in detached {
+                    handlers.join_all().await;
+                }
+            }
+        })
+        .await;
+
+        if drained.is_err() {
+            tracing::warn!(
+                "Detached handlers were still running {timeout:?} after shutdown, aborting them"
+            );
+            // dropping the join sets aborts their tasks
+            run_state.take_detached();
+        }
+
+        run_state.exit_requested().unwrap_or_default()
+    }
+
+    /// Drops every resource, most recently inserted first.
+    pub async fn clear_resources(&self) {
+        self.world.write().await.clear_resources().await;
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    ptr::NonNull,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use downcast_rs::{impl_downcast, DowncastSync};
+
+use crate::{
+    change_detection::ComponentTicks,
+    component::Component,
+    diagnostics::HeldLock,
+    entity::{Entity, EntityMap},
+    lock::Write,
+    util::TypeInfo,
+};
+
+/// How the components of a given type are laid out in memory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageType {
+    /// Every component is boxed and lives behind its own lock.
+    ///
+    /// Borrowing one entity's component never blocks borrowing another's.
+    #[default]
+    PerEntity,
+    /// All components of the type are packed into a single [`Column`] behind one lock.
+    ///
+    /// Iteration is cache-friendly and a query only takes the lock once, for reads and writes alike,
+    /// but a [`Mut`](crate::component::Mut) into the column blocks every other borrow of that type.
+    Column,
+}
+
+pub(crate) trait ColumnData: DowncastSync {
+    fn push_dyn(&mut self, component: Box<dyn Component>);
+
+    fn replace_dyn(&mut self, index: usize, component: Box<dyn Component>) -> Box<dyn Component>;
+
+    fn swap_remove_dyn(&mut self, index: usize) -> Box<dyn Component>;
+
+    fn drain_dyn(&mut self) -> Vec<Box<dyn Component>>;
+}
+impl_downcast!(sync ColumnData);
+
+impl<T: Component> ColumnData for Vec<T> {
+    fn push_dyn(&mut self, component: Box<dyn Component>) {
+        self.push(*component.downcast().unwrap_or_else(|_| unreachable!()));
+    }
+
+    fn replace_dyn(&mut self, index: usize, component: Box<dyn Component>) -> Box<dyn Component> {
+        let component = *component.downcast().unwrap_or_else(|_| unreachable!());
+        Box::new(std::mem::replace(&mut self[index], component))
+    }
+
+    fn swap_remove_dyn(&mut self, index: usize) -> Box<dyn Component> {
+        Box::new(self.swap_remove(index))
+    }
+
+    fn drain_dyn(&mut self) -> Vec<Box<dyn Component>> {
+        self.drain(..)
+            .map(|component| Box::new(component) as Box<dyn Component>)
+            .collect()
+    }
+}
+
+/// Dense storage for every component of a single type, indexed by entity.
+pub struct Column {
+    type_id: TypeInfo,
+    entities: Vec<Entity>,
+    indices: EntityMap<usize>,
+    ticks: Vec<ComponentTicks>,
+    data: Box<dyn ColumnData>,
+}
+
+impl Column {
+    pub fn new<T: Component>() -> Self {
+        Self {
+            type_id: TypeInfo::of::<T>(),
+            entities: Vec::new(),
+            indices: EntityMap::default(),
+            ticks: Vec::new(),
+            data: Box::new(Vec::<T>::new()),
+        }
+    }
+
+    pub fn type_id(&self) -> TypeInfo {
+        self.type_id
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.indices.contains_key(&entity)
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn index_of(&self, entity: Entity) -> Option<usize> {
+        self.indices.get(&entity).copied()
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        let index = self.index_of(entity)?;
+        Some(self.get_at(index))
+    }
+
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        let index = self.index_of(entity)?;
+        Some(self.get_at_mut(index))
+    }
+
+    pub fn ticks(&self, entity: Entity) -> Option<&ComponentTicks> {
+        let index = self.index_of(entity)?;
+        Some(self.ticks_at(index))
+    }
+
+    pub(crate) fn ticks_at(&self, index: usize) -> &ComponentTicks {
+        &self.ticks[index]
+    }
+
+    pub(crate) fn get_at<T: Component>(&self, index: usize) -> &T {
+        &self.data.downcast_ref::<Vec<T>>().unwrap()[index]
+    }
+
+    pub(crate) fn get_at_mut<T: Component>(&mut self, index: usize) -> &mut T {
+        &mut self.data.downcast_mut::<Vec<T>>().unwrap()[index]
+    }
+
+    pub(crate) fn insert_dyn(
+        &mut self,
+        entity: Entity,
+        component: Box<dyn Component>,
+        ticks: ComponentTicks,
+    ) -> Option<Box<dyn Component>> {
+        if let Some(index) = self.index_of(entity) {
+            self.ticks[index] = ticks;
+            return Some(self.data.replace_dyn(index, component));
+        }
+
+        self.indices.insert(entity, self.entities.len());
+        self.entities.push(entity);
+        self.ticks.push(ticks);
+        self.data.push_dyn(component);
+        None
+    }
+
+    pub(crate) fn remove_dyn(&mut self, entity: Entity) -> Option<Box<dyn Component>> {
+        let index = self.indices.remove(&entity)?;
+        self.entities.swap_remove(index);
+        self.ticks.swap_remove(index);
+        if let Some(moved) = self.entities.get(index) {
+            self.indices.insert(*moved, index);
+        }
+        Some(self.data.swap_remove_dyn(index))
+    }
+
+    pub(crate) fn drain_dyn(
+        &mut self,
+    ) -> impl Iterator<Item = (Entity, Box<dyn Component>, ComponentTicks)> {
+        self.indices.clear();
+        let entities = std::mem::take(&mut self.entities);
+        let ticks = std::mem::take(&mut self.ticks);
+        entities
+            .into_iter()
+            .zip(self.data.drain_dyn())
+            .zip(ticks)
+            .map(|((entity, component), ticks)| (entity, component, ticks))
+    }
+}
+
+/// A [`Column`] write-borrowed once and lent out one component at a time, so a query can hand out
+/// a [`Mut`](crate::component::Mut) for every entity in it without taking the lock per entity.
+pub(crate) struct ColumnWrite {
+    column: Write<Column>,
+    /// The column's first component, taken while `column` was borrowed exclusively.
+    data: NonNull<()>,
+    /// Which components were already lent out.
+    lent: Box<[AtomicBool]>,
+    _held: Option<HeldLock>,
+}
+
+// SAFETY: the column itself is `Send + Sync`, and `data` is only dereferenced at indices
+// handed out by `lend`, which never hands out the same one twice.
+unsafe impl Send for ColumnWrite {}
+unsafe impl Sync for ColumnWrite {}
+
+impl ColumnWrite {
+    pub(crate) fn new<T: Component>(mut column: Write<Column>, held: Option<HeldLock>) -> Self {
+        let data = column.data.downcast_mut::<Vec<T>>().unwrap();
+        let data = NonNull::from(data.as_mut_slice()).cast();
+        let lent = (0..column.len()).map(|_| AtomicBool::new(false)).collect();
+        Self {
+            column,
+            data,
+            lent,
+            _held: held,
+        }
+    }
+
+    pub(crate) fn index_of(&self, entity: Entity) -> Option<usize> {
+        self.column.index_of(entity)
+    }
+
+    pub(crate) fn ticks_at(&self, index: usize) -> &ComponentTicks {
+        self.column.ticks_at(index)
+    }
+
+    /// Marks the component at `index` as lent out, returning `false` if it already was.
+    pub(crate) fn lend(&self, index: usize) -> bool {
+        !self.lent[index].swap(true, Ordering::AcqRel)
+    }
+
+    /// # Safety
+    ///
+    /// `T` must be the column's component type, and the caller must own the loan for `index`
+    /// from [`lend`](Self::lend).
+    pub(crate) unsafe fn get_at<T: Component>(&self, index: usize) -> NonNull<T> {
+        debug_assert_eq!(self.column.type_id(), TypeInfo::of::<T>());
+        debug_assert!(self.lent[index].load(Ordering::Acquire));
+        // SAFETY: `index` was lent out, so it's in bounds
+        unsafe { self.data.cast::<T>().add(index) }
+    }
+}
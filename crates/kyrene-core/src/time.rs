@@ -0,0 +1,275 @@
+use std::time::{Duration, Instant};
+
+use crate::world_handle::WorldHandle;
+
+/// Frame timing, advanced once per [`WorldTick`](crate::world::WorldTick).
+#[derive(Debug, Clone)]
+pub struct Time {
+    last_update: Option<Instant>,
+    raw_delta: Duration,
+    delta: Duration,
+    elapsed: Duration,
+    frame_count: u64,
+    time_scale: f64,
+    paused: bool,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            last_update: None,
+            raw_delta: Duration::ZERO,
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl Time {
+    /// Time since the last tick, scaled by [`Time::time_scale`], or zero while paused.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Wall-clock time since the last tick, regardless of scale or pause.
+    pub fn raw_delta(&self) -> Duration {
+        self.raw_delta
+    }
+
+    /// Sum of every [`Time::delta`] so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Speeds up or slows down [`Time::delta`], e.g. `0.5` for half speed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time_scale` is negative or not finite.
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        assert!(
+            time_scale.is_finite() && time_scale >= 0.0,
+            "time scale must be finite and non-negative, got {time_scale}"
+        );
+        self.time_scale = time_scale;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Advances to `now`, returning the new [`Time::delta`].
+    pub fn update(&mut self, now: Instant) -> Duration {
+        self.raw_delta = self
+            .last_update
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last_update = Some(now);
+        self.frame_count += 1;
+
+        self.delta = if self.paused {
+            Duration::ZERO
+        } else {
+            self.raw_delta.mul_f64(self.time_scale)
+        };
+        self.elapsed += self.delta;
+
+        self.delta
+    }
+}
+
+/// Configures how often [`FixedUpdate`] fires, and keeps track of how far behind it is.
+#[derive(Debug, Clone)]
+pub struct FixedTime {
+    timestep: Duration,
+    max_steps: u32,
+    accumulator: Duration,
+    steps: u64,
+    /// When falling behind was last logged, and how many times it happened since.
+    last_warning: Option<Instant>,
+    skips_since_warning: u32,
+}
+
+impl Default for FixedTime {
+    /// 64 steps per second, catching up by at most 8 steps per tick.
+    fn default() -> Self {
+        Self::from_hz(64.0)
+    }
+}
+
+impl FixedTime {
+    /// How often falling behind is logged at most.
+    const WARNING_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// # Panics
+    ///
+    /// Panics if `timestep` is zero.
+    pub fn new(timestep: Duration) -> Self {
+        assert!(!timestep.is_zero(), "fixed timestep must not be zero");
+        Self {
+            timestep,
+            max_steps: 8,
+            accumulator: Duration::ZERO,
+            steps: 0,
+            last_warning: None,
+            skips_since_warning: 0,
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `hz` is not finite and positive.
+    pub fn from_hz(hz: f64) -> Self {
+        assert!(
+            hz.is_finite() && hz > 0.0,
+            "fixed update rate must be finite and positive, got {hz}"
+        );
+        Self::new(Duration::from_secs_f64(1.0 / hz))
+    }
+
+    /// Caps how many steps are run in a single tick to catch up.
+    ///
+    /// Any time beyond that is dropped, so a long stall slows the simulation down instead of making it spiral.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_steps` is zero.
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        assert!(max_steps >= 1, "max steps per tick must be at least 1");
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    /// Time accumulated towards the next step.
+    pub fn accumulator(&self) -> Duration {
+        self.accumulator
+    }
+
+    /// How far into the next step we are, from `0.0` to `1.0`, for interpolating between steps.
+    pub fn overstep_fraction(&self) -> f64 {
+        self.accumulator.as_secs_f64() / self.timestep.as_secs_f64()
+    }
+
+    /// Total number of steps run so far.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Adds `delta` to the accumulator, returning how many steps to run now.
+    pub fn accumulate(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+
+        let mut steps = 0;
+        while self.accumulator >= self.timestep && steps < self.max_steps {
+            self.accumulator -= self.timestep;
+            steps += 1;
+        }
+
+        if self.accumulator >= self.timestep {
+            self.warn_behind();
+            let remainder = self.accumulator.as_nanos() % self.timestep.as_nanos();
+            self.accumulator = Duration::from_nanos(remainder as u64);
+        }
+
+        self.steps += steps as u64;
+        steps
+    }
+
+    /// Logs that the accumulator is being skipped ahead, at most once per [`FixedTime::WARNING_INTERVAL`].
+    fn warn_behind(&mut self) {
+        self.skips_since_warning += 1;
+
+        let now = Instant::now();
+        if self
+            .last_warning
+            .is_some_and(|last| now.duration_since(last) < Self::WARNING_INTERVAL)
+        {
+            return;
+        }
+
+        tracing::warn!(
+            "FixedUpdate fell behind by {:?}, skipping ahead ({} time(s) since the last warning)",
+            self.accumulator,
+            self.skips_since_warning
+        );
+        self.last_warning = Some(now);
+        self.skips_since_warning = 0;
+    }
+}
+
+/// Fired [`FixedTime::timestep`] apart in game time, as many times per tick as needed to keep up.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedUpdate {
+    pub timestep: Duration,
+    /// Counts up from zero with every step.
+    pub step: u64,
+}
+
+impl WorldHandle {
+    /// Advances [`Time`] and fires a [`FixedUpdate`] for every step that has accumulated.
+    ///
+    /// Called once per tick, before [`WorldTick`](crate::world::WorldTick) is fired. Inserts default
+    /// [`Time`] and [`FixedTime`] resources if there aren't any yet.
+    pub async fn advance_time(&self) {
+        if !self.has_resource::<Time>().await {
+            self.insert_resource(Time::default()).await;
+        }
+        if !self.has_resource::<FixedTime>().await {
+            self.insert_resource(FixedTime::default()).await;
+        }
+
+        let delta = self
+            .get_resource_mut::<Time>()
+            .await
+            .unwrap()
+            .update(Instant::now());
+
+        let (steps, first_step, timestep) = {
+            let mut fixed_time = self.get_resource_mut::<FixedTime>().await.unwrap();
+            let steps = fixed_time.accumulate(delta);
+            (
+                steps,
+                fixed_time.steps() - steps as u64,
+                fixed_time.timestep(),
+            )
+        };
+
+        for step in first_step..first_step + steps as u64 {
+            self.fire_event(FixedUpdate { timestep, step }, true).await;
+        }
+    }
+}
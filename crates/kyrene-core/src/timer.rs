@@ -0,0 +1,212 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    component::Component,
+    event::{DynEventDispatcher, EventContext},
+    time::Time,
+    world_handle::WorldHandle,
+};
+
+/// Cancels an event scheduled with [`WorldHandle::fire_event_after`] and friends.
+///
+/// Dropping the handle doesn't cancel anything.
+#[derive(Clone, Debug, Default)]
+pub struct TimerHandle {
+    state: Arc<TimerState>,
+}
+
+#[derive(Debug)]
+struct TimerState {
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    max_catch_up: AtomicU32,
+}
+
+impl Default for TimerState {
+    fn default() -> Self {
+        Self {
+            cancelled: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            max_catch_up: AtomicU32::new(8),
+        }
+    }
+}
+
+impl TimerHandle {
+    /// Keeps the event from being fired again. Does nothing if it already finished.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Whether a one-shot event was fired, or a cancelled one was dropped.
+    pub fn is_finished(&self) -> bool {
+        self.state.finished.load(Ordering::Acquire)
+    }
+
+    /// Caps how many times a repeating event is fired in a single tick to catch up, 8 by default.
+    ///
+    /// Any intervals beyond that are dropped, so a long stall doesn't end in a burst of fires.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_catch_up` is zero.
+    pub fn with_max_catch_up(self, max_catch_up: u32) -> Self {
+        assert!(max_catch_up > 0, "Timer catch-up must be at least 1");
+        self.state
+            .max_catch_up
+            .store(max_catch_up, Ordering::Release);
+        self
+    }
+
+    pub fn max_catch_up(&self) -> u32 {
+        self.state.max_catch_up.load(Ordering::Acquire)
+    }
+}
+
+struct Timer {
+    dispatcher: DynEventDispatcher,
+    event: Arc<dyn Component>,
+    /// World time left until it's next fired.
+    remaining: Duration,
+    /// How often it's fired after that, if it repeats.
+    interval: Option<Duration>,
+    handle: TimerHandle,
+}
+
+/// Events waiting on world time to pass.
+#[derive(Default)]
+pub(crate) struct Timers {
+    timers: std::sync::Mutex<Vec<Timer>>,
+}
+
+impl Timers {
+    fn schedule(&self, timer: Timer) {
+        self.timers.lock().unwrap().push(timer);
+    }
+
+    /// Counts `delta` off every timer, returning the events that are due, in the order they came due.
+    fn advance(&self, delta: Duration) -> Vec<(DynEventDispatcher, Arc<dyn Component>)> {
+        let mut due = Vec::new();
+        let mut timers = self.timers.lock().unwrap();
+
+        timers.retain_mut(|timer| {
+            if timer.handle.is_cancelled() {
+                timer.handle.state.finished.store(true, Ordering::Release);
+                return false;
+            }
+
+            let max_catch_up = timer.handle.max_catch_up();
+            let mut fired = 0;
+            let mut elapsed = delta;
+            while elapsed >= timer.remaining {
+                if fired == max_catch_up {
+                    // drop the intervals past the cap, staying in step with the ones that were fired
+                    let interval = timer.remaining.as_nanos();
+                    elapsed = Duration::from_nanos((elapsed.as_nanos() % interval) as u64);
+                    break;
+                }
+                elapsed -= timer.remaining;
+                fired += 1;
+                due.push((elapsed, timer.dispatcher.clone(), timer.event.clone()));
+                match timer.interval {
+                    Some(interval) => timer.remaining = interval,
+                    None => {
+                        timer.handle.state.finished.store(true, Ordering::Release);
+                        return false;
+                    }
+                }
+            }
+            timer.remaining -= elapsed;
+            true
+        });
+
+        // whatever came due earliest in the tick has the most time left over
+        due.sort_by_key(|(left_over, ..)| std::cmp::Reverse(*left_over));
+        due.into_iter()
+            .map(|(_, dispatcher, event)| (dispatcher, event))
+            .collect()
+    }
+}
+
+impl WorldHandle {
+    /// Fires `event` once `delay` has passed in world time, so it's held back while [`Time`] is paused
+    /// and sped up or slowed down by its time scale.
+    ///
+    /// It's fired during the first tick that reaches the delay, before [`WorldTick`](crate::world::WorldTick),
+    /// and its handlers are awaited.
+    pub async fn fire_event_after<T: Component>(&self, event: T, delay: Duration) -> TimerHandle {
+        self.schedule_event(event, delay, None).await
+    }
+
+    /// Fires `event` once world time has caught up to `instant`.
+    ///
+    /// The wait is measured from now, so pausing or scaling [`Time`] in the meantime moves it.
+    pub async fn fire_event_at<T: Component>(&self, event: T, instant: Instant) -> TimerHandle {
+        let delay = instant.saturating_duration_since(Instant::now());
+        self.schedule_event(event, delay, None).await
+    }
+
+    /// Fires `event` every `interval` of world time, starting one `interval` from now, until cancelled.
+    ///
+    /// If a tick takes longer than `interval`, the event is fired once for every interval that passed,
+    /// up to [`TimerHandle::with_max_catch_up`] times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub async fn fire_event_every<T: Component>(
+        &self,
+        event: T,
+        interval: Duration,
+    ) -> TimerHandle {
+        assert!(!interval.is_zero(), "Timer interval must be non-zero");
+        self.schedule_event(event, interval, Some(interval)).await
+    }
+
+    async fn schedule_event<T: Component>(
+        &self,
+        event: T,
+        delay: Duration,
+        interval: Option<Duration>,
+    ) -> TimerHandle {
+        let (dispatcher, timers) = {
+            let world = self.world.read().await;
+            let dispatcher = world.get_event::<T>().unwrap().into_dyn();
+            (dispatcher, world.timers())
+        };
+
+        let handle = TimerHandle::default();
+        timers.schedule(Timer {
+            dispatcher,
+            event: Arc::new(event),
+            remaining: delay,
+            interval,
+            handle: handle.clone(),
+        });
+        handle
+    }
+
+    /// Counts this tick's [`Time::delta`] off every scheduled event, firing the ones that are due.
+    pub(crate) async fn advance_timers(&self) {
+        let Some(delta) = self.get_resource::<Time>().await.map(|time| time.delta()) else {
+            return;
+        };
+
+        let due = self.world.read().await.timers().advance(delta);
+        for (dispatcher, event) in due {
+            dispatcher
+                .fire_dyn(self.clone(), event, EventContext::default(), true)
+                .await;
+        }
+    }
+}
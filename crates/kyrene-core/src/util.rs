@@ -1,14 +1,41 @@
 use std::{
     any::TypeId,
     fmt::Debug,
+    future::Future,
     hash::{BuildHasherDefault, Hash, Hasher},
     ops::{Deref, DerefMut},
+    pin::Pin,
+    task::{Context, Poll},
 };
 
+/// Like [`BoxFuture`](futures::future::BoxFuture), but also `Sync`, as required of handler futures.
+pub type SyncBoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + Sync + 'a>>;
+
+/// Lets a future that's only `Send` be awaited inside one that has to be `Sync`.
+pub(crate) struct SyncFuture<F>(F);
+
+impl<F> SyncFuture<F> {
+    pub(crate) fn new(future: F) -> Self {
+        Self(future)
+    }
+}
+
+// SAFETY: a future can only be polled through `Pin<&mut Self>`, and nothing here takes `&self`,
+// so a shared reference gives no access to `F` at all
+unsafe impl<F: Send> Sync for SyncFuture<F> {}
+
+impl<F: Future> Future for SyncFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `F` is structurally pinned, and never moved out of `self`
+        unsafe { self.map_unchecked_mut(|this| &mut this.0) }.poll(cx)
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct TypeInfo {
     pub type_id: TypeId,
-    #[cfg(debug_assertions)]
     pub type_name: &'static str,
 }
 
@@ -16,7 +43,6 @@ impl TypeInfo {
     pub fn of<T: 'static>() -> Self {
         Self {
             type_id: TypeId::of::<T>(),
-            #[cfg(debug_assertions)]
             type_name: std::any::type_name::<T>(),
         }
     }
@@ -30,14 +56,7 @@ impl Hash for TypeInfo {
 
 impl Debug for TypeInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        #[cfg(debug_assertions)]
-        {
-            write!(f, "{}", self.type_name)
-        }
-        #[cfg(not(debug_assertions))]
-        {
-            write!(f, "{:?}", self.type_id)
-        }
+        write!(f, "{}", self.type_name)
     }
 }
 
@@ -117,6 +136,16 @@ impl<T> DerefMut for TypeIdMap<T> {
     }
 }
 
+impl<T> IntoIterator for TypeIdMap<T> {
+    type Item = (TypeInfo, T);
+    type IntoIter =
+        <hashbrown::HashMap<TypeInfo, T, BuildHasherDefault<TypeIdHasher>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
 // pub type TypeIdSet = hashbrown::HashSet<TypeInfo, BuildHasherDefault<TypeIdHasher>>;
 
 #[derive(Clone, Debug, Default)]
@@ -1,17 +1,31 @@
-use std::sync::Arc;
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
 
+use tokio::sync::Notify;
 use tracing::level_filters::LevelFilter;
 
 use crate::{
     bundle::Bundle,
-    component::{Component, Components, Mut, Ref},
-    entity::{Entities, Entity},
-    event::EventDispatcher,
-    handler::{Events, IntoHandlerConfig},
+    change_detection::{ChangeTick, ComponentTicks},
+    commands::CommandQueue,
+    component::{Component, ComponentInfo, ComponentLoan, Components, LifecycleEvent, Mut, Ref},
+    entity::{Entities, Entity, EntityMap},
+    error::HandlerFailed,
+    event::{DynEventDispatcher, EventDispatcher},
+    event_queue::{swap_queue, EventQueue, SwapQueue},
+    handler::{DynEventHandlers, Events, HandlerId, IntoHandlerConfig},
+    handler_graph::{HandlerGraph, HandlerGraphs, HandlerValidationReport},
+    handler_set::IntoHandlerSetConfig,
     lock::RwLock,
     plugin::Plugin,
-    resource::Resources,
-    util::TypeInfo,
+    resource::{with_timeout, ResourceEvent, ResourceInfo, ResourceTimeout, Resources},
+    runner::{tick_interval, AppExit, RunState, DEFAULT_SHUTDOWN_TIMEOUT, DEFAULT_TICK_RATE},
+    storage::{Column, StorageType},
+    time::FixedUpdate,
+    timer::Timers,
+    util::{TypeIdMap, TypeInfo},
     world_handle::WorldHandle,
 };
 
@@ -20,20 +34,38 @@ pub struct World {
     components: Components,
     resources: Resources,
     events: Events,
+    pending_events: Vec<(DynEventDispatcher, Arc<dyn Component>)>,
+    commands: CommandQueue,
+    event_queues: TypeIdMap<SwapQueue>,
+    observers: EntityMap<Vec<HandlerId>>,
+    tick: u64,
+    run_state: Arc<RunState>,
+    timers: Arc<Timers>,
 }
 
 #[allow(clippy::derivable_impls)]
 impl Default for World {
     fn default() -> Self {
+        let change_tick = ChangeTick::default();
         let mut this = Self {
             entities: Entities::default(),
-            components: Components::default(),
-            resources: Resources::default(),
+            components: Components::new(change_tick.clone()),
+            resources: Resources::new(change_tick),
             events: Events::default(),
+            pending_events: Vec::new(),
+            commands: CommandQueue::default(),
+            event_queues: TypeIdMap::default(),
+            observers: EntityMap::default(),
+            tick: 0,
+            run_state: Arc::default(),
+            timers: Arc::default(),
         };
         this.add_event::<WorldStartup>();
         this.add_event::<WorldTick>();
         this.add_event::<WorldShutdown>();
+        this.add_event::<FixedUpdate>();
+        this.add_event::<AppExit>();
+        this.add_event::<HandlerFailed>();
         this
     }
 }
@@ -48,29 +80,144 @@ impl World {
     }
 
     pub fn entity(&mut self) -> Entity {
+        self.flush_entities();
         self.entities.alloc()
     }
 
+    /// Reserves an entity without mutable access to the world. It becomes alive on the next [`World::flush_entities`].
+    pub fn reserve_entity(&self) -> Entity {
+        self.entities.reserve()
+    }
+
+    /// Makes every entity reserved through [`World::reserve_entity`] alive.
+    pub fn flush_entities(&mut self) {
+        self.entities.flush();
+    }
+
     pub fn entity_iter(&self) -> impl Iterator<Item = Entity> + use<'_> {
         self.components.entity_iter()
     }
 
     pub async fn insert<T: Component>(&mut self, entity: Entity, component: T) -> Option<T> {
-        self.components.insert(entity, component).await
+        let old = self
+            .insert_dyn(entity, ComponentInfo::of::<T>(), Box::new(component))
+            .await?;
+        let old: T = *old.downcast().unwrap_or_else(|_| unreachable!());
+        Some(old)
     }
 
-    pub fn insert_bundle<T: Bundle>(&mut self, entity: Entity, bundle: T) {
-        self.components.insert_bundle(entity, bundle);
+    /// Inserts every component in `bundle` on the entity.
+    ///
+    /// This is `async` because components stored in a [`Column`](StorageType::Column)
+    /// are inserted under the column's lock.
+    pub async fn insert_bundle<T: Bundle>(&mut self, entity: Entity, bundle: T) {
+        for (info, component) in bundle.into_dyn_components() {
+            self.insert_dyn(entity, info, component).await;
+        }
     }
 
-    pub fn spawn<T: Bundle>(&mut self, bundle: T) -> Entity {
+    pub(crate) async fn insert_dyn(
+        &mut self,
+        entity: Entity,
+        info: ComponentInfo,
+        component: Box<dyn Component>,
+    ) -> Option<Box<dyn Component>> {
+        let old = self.components.insert_dyn(entity, info, component).await;
+        if old.is_none() {
+            self.queue_component_event(info.on_add, entity);
+        }
+        self.queue_component_event(info.on_insert, entity);
+        old
+    }
+
+    /// Creates an entity with the components in `bundle`. `async` for the same reason as [`World::insert_bundle`].
+    pub async fn spawn<T: Bundle>(&mut self, bundle: T) -> Entity {
         let entity = self.entity();
-        self.insert_bundle(entity, bundle);
+        self.insert_bundle(entity, bundle).await;
         entity
     }
 
     pub async fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
-        self.components.remove(entity).await
+        let component = self.components.remove(entity).await?;
+        self.queue_component_event(ComponentInfo::of::<T>().on_remove, entity);
+        Some(component)
+    }
+
+    /// Destroys the entity and all of its components.
+    ///
+    /// Its children are left without a parent; use [`World::despawn_recursive`] to take them along.
+    ///
+    /// Returns `false` if the entity was already dead.
+    pub async fn despawn(&mut self, entity: Entity) -> bool {
+        self.flush_entities();
+        if !self.entities.is_alive(entity) {
+            return false;
+        }
+
+        self.detach_from_hierarchy(entity).await;
+        self.remove_observers(entity);
+
+        for info in self.components.despawn(entity).await.unwrap_or_default() {
+            self.queue_component_event(info.on_remove, entity);
+        }
+        self.entities.free(entity);
+        true
+    }
+
+    /// Queues a component lifecycle event, if anything is listening for it.
+    fn queue_component_event(&mut self, event: LifecycleEvent, entity: Entity) {
+        self.queue_event(event.type_id, || (event.new)(entity));
+    }
+
+    fn queue_resource_event(&mut self, event: ResourceEvent) {
+        self.queue_event(event.type_id, event.new);
+    }
+
+    fn queue_event(&mut self, event_type_id: TypeInfo, new: impl FnOnce() -> Arc<dyn Component>) {
+        if let Some(dispatcher) = self.events.entries.get(&event_type_id) {
+            self.pending_events.push((dispatcher.clone(), new()));
+        }
+    }
+
+    /// Queues a [`ResourceChanged`](crate::resource::ResourceChanged) for every resource mutated since the last call.
+    pub(crate) fn queue_resource_changes(&mut self) {
+        for info in self.resources.take_changed() {
+            self.queue_resource_event(info.on_change);
+        }
+    }
+
+    pub(crate) fn command_queue(&self) -> CommandQueue {
+        self.commands.clone()
+    }
+
+    /// Takes the lifecycle events queued since the last call, to be fired once the world is no longer borrowed.
+    pub(crate) fn take_pending_events(&mut self) -> Vec<(DynEventDispatcher, Arc<dyn Component>)> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    pub fn change_tick(&self) -> &ChangeTick {
+        self.components.change_tick()
+    }
+
+    pub fn storage_type<T: Component>(&self) -> StorageType {
+        self.components.storage_type::<T>()
+    }
+
+    /// Changes how components of type `T` are stored. See [`StorageType`].
+    pub async fn set_storage_type<T: Component>(&mut self, storage_type: StorageType) {
+        self.components.set_storage_type::<T>(storage_type).await;
+    }
+
+    pub(crate) fn component_loan<T: Component>(&self, entity: Entity) -> Option<ComponentLoan> {
+        self.components.loan::<T>(entity)
+    }
+
+    pub(crate) fn column<T: Component>(&self) -> Option<Arc<RwLock<Column>>> {
+        self.components.column::<T>()
     }
 
     pub async fn get<T: Component>(&self, entity: Entity) -> Option<Ref<T>> {
@@ -90,11 +237,19 @@ impl World {
     }
 
     pub async fn insert_resource<T: Component>(&mut self, resource: T) -> Option<T> {
-        self.resources.insert(resource).await
+        let old = self.resources.insert(resource).await;
+        self.queue_resource_event(ResourceInfo::of::<T>().on_insert);
+        old
     }
 
     pub async fn remove_resource<T: Component>(&mut self) -> Option<T> {
-        self.resources.remove::<T>().await
+        let resource = self.resources.remove::<T>().await?;
+        self.queue_resource_event(ResourceInfo::of::<T>().on_remove);
+        Some(resource)
+    }
+
+    pub fn resource_ticks<T: Component>(&self) -> Option<ComponentTicks> {
+        self.resources.ticks::<T>()
     }
 
     pub fn has_resource<T: Component>(&self) -> bool {
@@ -113,12 +268,27 @@ impl World {
         self.resources.get_mut::<T>().await
     }
 
-    pub async fn await_resource<T: Component>(&mut self) -> Ref<T> {
-        self.resources.wait_for::<T>().await
+    /// Waits until a `T` is inserted, or `timeout` elapses.
+    ///
+    /// Inserting needs the world mutably, so when it's shared prefer [`WorldHandle::await_resource`],
+    /// which doesn't keep the world borrowed while it waits.
+    pub async fn await_resource<T: Component>(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Ref<T>, ResourceTimeout> {
+        with_timeout::<T, _>(timeout, self.resources.wait_for::<T>()).await
+    }
+
+    /// Waits until a `T` is inserted, or `timeout` elapses. See [`World::await_resource`].
+    pub async fn await_resource_mut<T: Component>(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Mut<T>, ResourceTimeout> {
+        with_timeout::<T, _>(timeout, self.resources.wait_for_mut::<T>()).await
     }
 
-    pub async fn await_resource_mut<T: Component>(&mut self) -> Mut<T> {
-        self.resources.wait_for_mut::<T>().await
+    pub(crate) fn resource_inserted<T: Component>(&self) -> Arc<Notify> {
+        self.resources.inserted::<T>()
     }
 
     #[track_caller]
@@ -135,13 +305,134 @@ impl World {
     }
 
     #[track_caller]
-    pub fn add_event_handler<T, F, M>(&mut self, handler: F)
+    pub fn add_event_handler<T, F, M>(&mut self, handler: F) -> HandlerId
+    where
+        T: Component,
+        F: IntoHandlerConfig<M, Event = T> + 'static,
+        M: 'static,
+    {
+        self.events.add_handler(handler)
+    }
+
+    /// Adds a handler that only runs for events fired at `entity` with
+    /// [`WorldHandle::fire_entity_event`], or bubbled up to it from one of its descendants.
+    ///
+    /// It's removed along with the entity when that's despawned.
+    #[track_caller]
+    pub fn observe<T, F, M>(&mut self, entity: Entity, handler: F) -> HandlerId
     where
         T: Component,
         F: IntoHandlerConfig<M, Event = T> + 'static,
         M: 'static,
     {
-        self.events.add_handler(handler);
+        let id = self.events.add_handler(handler.finish().observing(entity));
+        self.add_observer(entity, id);
+        id
+    }
+
+    pub(crate) fn add_observer(&mut self, entity: Entity, id: HandlerId) {
+        if self.is_alive(entity) {
+            self.observers.entry(entity).or_default().push(id);
+        } else if let Some(handlers) = self.handlers_of(id) {
+            handlers.remove(id);
+        }
+    }
+
+    fn remove_observers(&mut self, entity: Entity) {
+        for id in self.observers.remove(&entity).unwrap_or_default() {
+            if let Some(handlers) = self.handlers_of(id) {
+                // fine even if the entity is despawning itself from one of them
+                handlers.remove(id);
+            }
+        }
+    }
+
+    /// Removes a handler added with [`World::add_event_handler`], returning `false` if it was already gone.
+    ///
+    /// This never waits on its event, so a handler can remove itself or any other handler of the
+    /// event being fired. A fire that's already in progress still runs it.
+    pub fn remove_handler(&self, id: HandlerId) -> bool {
+        match self.handlers_of(id) {
+            Some(handlers) => handlers.remove(id),
+            None => false,
+        }
+    }
+
+    /// Disabled handlers are skipped when their event is fired. Returns `false` if there's no such handler.
+    pub fn set_handler_enabled(&self, id: HandlerId, enabled: bool) -> bool {
+        match self.handlers_of(id) {
+            Some(handlers) => handlers.set_enabled(id, enabled),
+            None => false,
+        }
+    }
+
+    pub(crate) fn handlers_of(&self, id: HandlerId) -> Option<DynEventHandlers> {
+        self.events.handlers_of(id)
+    }
+
+    pub fn handler_ids<T: Component>(&self) -> Vec<HandlerId> {
+        match self.get_event::<T>() {
+            Some(event) => event.handler_ids(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Inserts an empty [`EventQueue<T>`] if there isn't one, and swaps it every tick from then on.
+    pub async fn add_event_queue<T: Component>(&mut self) {
+        if self.event_queues.contains_type::<T>() {
+            return;
+        }
+        self.event_queues.insert_for::<T>(swap_queue::<T>);
+        if !self.has_resource::<EventQueue<T>>() {
+            self.insert_resource(EventQueue::<T>::new()).await;
+        }
+    }
+
+    pub fn has_event_queue<T: Component>(&self) -> bool {
+        self.event_queues.contains_type::<T>()
+    }
+
+    pub(crate) async fn swap_event_queues(&self) {
+        for swap in self.event_queues.values() {
+            swap(self).await;
+        }
+    }
+
+    /// Configures ordering and run conditions for a [`HandlerSet`](crate::handler_set::HandlerSet)'s handlers of event `T`.
+    ///
+    /// Configuring the same set again adds to what's already there.
+    pub fn configure_set<T: Component>(&mut self, config: impl IntoHandlerSetConfig) {
+        self.events.add_event::<T>().configure_set(config);
+    }
+
+    /// Resolves the ordering constraints of every event's handlers and reports anything wrong with them,
+    /// such as cycles or handlers ordered against ones that were never added.
+    ///
+    /// The same problems are logged the first time an event is fired after its handlers change.
+    pub async fn validate_handlers(&self) -> HandlerValidationReport {
+        let mut problems = Vec::new();
+        for dispatcher in self.events.entries.values() {
+            dispatcher.handlers.dirty.store(false, Ordering::Release);
+            problems.extend(dispatcher.handlers.resolve(dispatcher.type_name));
+        }
+        HandlerValidationReport { problems }
+    }
+
+    /// Snapshots the handlers of event `T` and the order they run in, for rendering with
+    /// [`HandlerGraph::to_dot`] or [`HandlerGraph::to_json`].
+    pub async fn handler_graph<T: Component>(&self) -> Option<HandlerGraph> {
+        let dispatcher = self.events.entries.get_for::<T>()?;
+        Some(dispatcher.handlers.graph(dispatcher.type_name))
+    }
+
+    /// Snapshots the handlers of every event, sorted by event type name.
+    pub async fn dump_all_handler_graphs(&self) -> HandlerGraphs {
+        let mut graphs = Vec::new();
+        for dispatcher in self.events.entries.values() {
+            graphs.push(dispatcher.handlers.graph(dispatcher.type_name));
+        }
+        graphs.sort_by_key(|graph| graph.event_type);
+        HandlerGraphs { graphs }
     }
 
     pub fn into_world_handle(self) -> WorldHandle {
@@ -150,13 +441,16 @@ impl World {
         }
     }
 
-    pub fn run(self) {
-        tracing::subscriber::set_global_default(
+    /// Runs the world until an [`AppExit`] is fired, then shuts it down and returns the exit.
+    ///
+    /// Ticks [`DEFAULT_TICK_RATE`] times per second.
+    pub fn run(self) -> AppExit {
+        // someone else may have already installed one
+        let _ = tracing::subscriber::set_global_default(
             tracing_subscriber::FmtSubscriber::builder()
                 .with_max_level(LevelFilter::DEBUG)
                 .finish(),
-        )
-        .unwrap();
+        );
 
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -166,24 +460,41 @@ impl World {
         let world = self.into_world_handle();
 
         runtime.block_on(async move {
-            world.fire_event(WorldStartup, true).await;
-
-            // spawn WorldTick task
-            let mut tick = 0;
-            tokio::spawn({
-                let world = world.clone();
-                async move {
-                    loop {
-                        tick += 1;
-                        world.fire_event(WorldTick { tick }, true).await;
-                    }
-                }
-            });
-
-            loop {
-                tokio::task::yield_now().await;
+            world.startup().await;
+
+            let mut interval = tick_interval(DEFAULT_TICK_RATE);
+            while world.exit_requested().await.is_none() {
+                interval.tick().await;
+                world.update().await;
             }
-        });
+
+            let exit = world.shutdown(DEFAULT_SHUTDOWN_TIMEOUT).await;
+            world.clear_resources().await;
+            exit
+        })
+    }
+
+    /// The number of the last [`WorldTick`] fired.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub(crate) fn run_state(&self) -> Arc<RunState> {
+        self.run_state.clone()
+    }
+
+    pub(crate) fn timers(&self) -> Arc<Timers> {
+        self.timers.clone()
+    }
+
+    /// Drops every resource, most recently inserted first.
+    pub async fn clear_resources(&mut self) {
+        self.resources.clear().await;
+    }
+
+    pub(crate) fn advance_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
     }
 }
 
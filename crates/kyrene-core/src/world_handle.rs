@@ -1,17 +1,21 @@
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use async_fn_traits::AsyncFnMut2;
 use futures::StreamExt;
 
 use crate::{
     bundle::Bundle,
-    component::{Component, Mut, Ref},
+    change_detection::{ChangeTick, ComponentTicks},
+    commands::Command,
+    component::{Component, ComponentLoan, Mut, Ref},
     entity::{Entity, EntitySet},
-    event::EventDispatcher,
-    handler::{EventHandlerMeta, HandlerParam},
+    event::{DynEventDispatcher, EventContext, EventDispatcher},
+    handler::{EventHandlerMeta, HandlerId, HandlerParam, IntoHandlerConfig},
     lock::RwLock,
     query::{Query, Queryable},
-    util::TypeInfo,
+    resource::{wait_for_insert, with_timeout, ResourceTimeout},
+    storage::{Column, StorageType},
+    util::{SyncBoxFuture, TypeInfo},
     world::World,
 };
 
@@ -36,27 +40,161 @@ impl WorldHandle {
     }
 
     pub async fn insert<T: Component>(&self, entity: Entity, component: T) -> Option<T> {
-        self.world.write().await.insert(entity, component).await
+        let (old, events) = {
+            let mut world = self.world.write().await;
+            let old = world.insert(entity, component).await;
+            (old, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        old
     }
 
     pub async fn insert_bundle<T: Bundle>(&self, entity: Entity, bundle: T) {
-        self.world.write().await.insert_bundle(entity, bundle);
+        let events = {
+            let mut world = self.world.write().await;
+            world.insert_bundle(entity, bundle).await;
+            world.take_pending_events()
+        };
+        self.fire_pending_events(events).await;
     }
 
     pub async fn spawn<T: Bundle>(&self, bundle: T) -> Entity {
-        self.world.write().await.spawn(bundle)
+        let (entity, events) = {
+            let mut world = self.world.write().await;
+            let entity = world.spawn(bundle).await;
+            (entity, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        entity
     }
 
     pub async fn remove<T: Component>(&self, entity: Entity) -> Option<T> {
-        self.world.write().await.remove::<T>(entity).await
+        let (component, events) = {
+            let mut world = self.world.write().await;
+            let component = world.remove::<T>(entity).await;
+            (component, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        component
+    }
+
+    pub async fn despawn(&self, entity: Entity) -> bool {
+        let (despawned, events) = {
+            let mut world = self.world.write().await;
+            let despawned = world.despawn(entity).await;
+            (despawned, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        despawned
+    }
+
+    /// Applies every command queued through [`Commands`](crate::commands::Commands) so far.
+    pub async fn apply_commands(&self) {
+        let queue = self.world.read().await.command_queue();
+        self.apply_command_list(queue.take().await).await;
+    }
+
+    pub(crate) async fn apply_command_list(&self, commands: Vec<Command>) {
+        if commands.is_empty() {
+            return;
+        }
+
+        let events = {
+            let mut world = self.world.write().await;
+            world.flush_entities();
+            for command in commands {
+                command(&mut world).await;
+            }
+            world.take_pending_events()
+        };
+        self.fire_pending_events(events).await;
+    }
+
+    /// Fires any lifecycle events that were queued while the [`World`] was borrowed directly,
+    /// e.g. by inserting components or resources while building plugins.
+    pub async fn flush_pending_events(&self) {
+        let events = self.world.write().await.take_pending_events();
+        self.fire_pending_events(events).await;
+    }
+
+    // boxed, since the handlers may in turn apply commands and fire more of these
+    pub(crate) fn fire_pending_events(
+        &self,
+        events: Vec<(DynEventDispatcher, Arc<dyn Component>)>,
+    ) -> SyncBoxFuture<'_, ()> {
+        Box::pin(async move {
+            for (dispatcher, event) in events {
+                dispatcher
+                    .fire_dyn(self.clone(), event, EventContext::default(), true)
+                    .await;
+            }
+        })
+    }
+
+    pub async fn is_alive(&self, entity: Entity) -> bool {
+        self.world.read().await.is_alive(entity)
     }
 
     pub async fn get<T: Component>(&self, entity: Entity) -> Option<Ref<T>> {
-        self.world.read().await.get::<T>(entity).await
+        self.get_since::<T>(entity, 0).await
     }
 
     pub async fn get_mut<T: Component>(&self, entity: Entity) -> Option<Mut<T>> {
-        self.world.read().await.get_mut::<T>(entity).await
+        self.get_mut_since::<T>(entity, 0).await
+    }
+
+    /// Like [`get`](Self::get), but change detection on the returned [`Ref`] is relative to `last_run`.
+    pub async fn get_since<T: Component>(&self, entity: Entity, last_run: u64) -> Option<Ref<T>> {
+        // don't hold the world lock while waiting on the component's lock
+        let loan = self.world.read().await.component_loan::<T>(entity)?;
+        loan.read(entity, last_run).await
+    }
+
+    /// Like [`get_mut`](Self::get_mut), but change detection on the returned [`Mut`] is relative to `last_run`.
+    pub async fn get_mut_since<T: Component>(
+        &self,
+        entity: Entity,
+        last_run: u64,
+    ) -> Option<Mut<T>> {
+        let (loan, change_tick) = {
+            let world = self.world.read().await;
+            (
+                world.component_loan::<T>(entity)?,
+                world.change_tick().clone(),
+            )
+        };
+        loan.write(entity, change_tick, last_run).await
+    }
+
+    pub(crate) async fn component_loans<T: Component>(
+        &self,
+        entities: impl IntoIterator<Item = Entity>,
+    ) -> Vec<(Entity, ComponentLoan)> {
+        let world = self.world.read().await;
+        entities
+            .into_iter()
+            .filter_map(|entity| Some((entity, world.component_loan::<T>(entity)?)))
+            .collect()
+    }
+
+    pub async fn change_tick(&self) -> ChangeTick {
+        self.world.read().await.change_tick().clone()
+    }
+
+    pub async fn storage_type<T: Component>(&self) -> StorageType {
+        self.world.read().await.storage_type::<T>()
+    }
+
+    pub async fn set_storage_type<T: Component>(&self, storage_type: StorageType) {
+        self.world
+            .write()
+            .await
+            .set_storage_type::<T>(storage_type)
+            .await;
+    }
+
+    pub(crate) async fn column<T: Component>(&self) -> Option<Arc<RwLock<Column>>> {
+        self.world.read().await.column::<T>()
     }
 
     pub async fn has<T: Component>(&self, entity: Entity) -> bool {
@@ -71,6 +209,10 @@ impl WorldHandle {
         Query::new(self.clone()).await
     }
 
+    pub async fn query_filtered<Q: Queryable, F: Queryable>(&self) -> Query<Q, F> {
+        Query::new(self.clone()).await
+    }
+
     pub async fn query_iter<Q>(&self, mut f: impl AsyncFnMut2<Self, Q::Item>)
     where
         Q: Queryable,
@@ -83,11 +225,37 @@ impl WorldHandle {
     }
 
     pub async fn insert_resource<T: Component>(&self, resource: T) -> Option<T> {
-        self.world.write().await.insert_resource(resource).await
+        let (old, events) = {
+            let mut world = self.world.write().await;
+            let old = world.insert_resource(resource).await;
+            (old, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        old
     }
 
     pub async fn remove_resource<T: Component>(&self) -> Option<T> {
-        self.world.write().await.remove_resource::<T>().await
+        let (resource, events) = {
+            let mut world = self.world.write().await;
+            let resource = world.remove_resource::<T>().await;
+            (resource, world.take_pending_events())
+        };
+        self.fire_pending_events(events).await;
+        resource
+    }
+
+    pub async fn resource_ticks<T: Component>(&self) -> Option<ComponentTicks> {
+        self.world.read().await.resource_ticks::<T>()
+    }
+
+    /// Fires [`ResourceChanged`](crate::resource::ResourceChanged) for every resource mutated since the last call.
+    pub async fn flush_resource_changes(&self) {
+        let events = {
+            let mut world = self.world.write().await;
+            world.queue_resource_changes();
+            world.take_pending_events()
+        };
+        self.fire_pending_events(events).await;
     }
 
     pub async fn has_resource<T: Component>(&self) -> bool {
@@ -106,6 +274,34 @@ impl WorldHandle {
         self.world.read().await.get_resource_mut::<T>().await
     }
 
+    /// Waits until a `T` is inserted, or `timeout` elapses.
+    ///
+    /// Unlike [`World::await_resource`], the world isn't kept borrowed while waiting, so the resource can actually be inserted.
+    pub async fn await_resource<T: Component>(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Ref<T>, ResourceTimeout> {
+        let inserted = || async { self.world.read().await.resource_inserted::<T>() };
+        with_timeout::<T, _>(
+            timeout,
+            wait_for_insert(inserted, || self.get_resource::<T>()),
+        )
+        .await
+    }
+
+    /// Waits until a `T` is inserted, or `timeout` elapses. See [`WorldHandle::await_resource`].
+    pub async fn await_resource_mut<T: Component>(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Mut<T>, ResourceTimeout> {
+        let inserted = || async { self.world.read().await.resource_inserted::<T>() };
+        with_timeout::<T, _>(
+            timeout,
+            wait_for_insert(inserted, || self.get_resource_mut::<T>()),
+        )
+        .await
+    }
+
     pub async fn add_event<T: Component>(&self) -> EventDispatcher<T> {
         self.world.write().await.add_event::<T>()
     }
@@ -122,6 +318,67 @@ impl WorldHandle {
         let dis = { self.world.read().await.get_event::<T>().unwrap() };
         dis.fire(self.clone(), event, await_all_handlers).await
     }
+
+    /// Fires `event` at `target`, then at its [`Parent`](crate::hierarchy::Parent), and so on up to the root,
+    /// stopping early once a handler calls [`stop_propagation`](crate::event::EventInner::stop_propagation).
+    ///
+    /// The event's handlers run once, at `target`, and the handlers observing each entity it reaches
+    /// run when it gets there. They can tell which one that is with
+    /// [`current_target`](crate::event::EventInner::current_target), and are always awaited.
+    /// Returns how many handlers were fired in total.
+    pub async fn fire_entity_event<T: Component>(&self, target: Entity, event: T) -> usize {
+        let dis = { self.world.read().await.get_event::<T>().unwrap() };
+        dis.fire_at(self.clone(), target, event).await
+    }
+
+    /// Adds a handler while the world is running. It runs from the next time its event is fired.
+    pub async fn add_event_handler<T, F, M>(&self, handler: F) -> HandlerId
+    where
+        T: Component,
+        F: IntoHandlerConfig<M, Event = T>,
+        M: 'static,
+    {
+        self.add_event::<T>().await.add_handler(handler)
+    }
+
+    /// See [`World::observe`].
+    pub async fn observe<T, F, M>(&self, entity: Entity, handler: F) -> HandlerId
+    where
+        T: Component,
+        F: IntoHandlerConfig<M, Event = T>,
+        M: 'static,
+    {
+        let event = self.add_event::<T>().await;
+        let id = event.add_handler(handler.finish().observing(entity));
+        self.world.write().await.add_observer(entity, id);
+        id
+    }
+
+    /// See [`World::remove_handler`].
+    pub async fn remove_handler(&self, id: HandlerId) -> bool {
+        let handlers = self.world.read().await.handlers_of(id);
+        match handlers {
+            Some(handlers) => handlers.remove(id),
+            None => false,
+        }
+    }
+
+    /// See [`World::set_handler_enabled`].
+    pub async fn set_handler_enabled(&self, id: HandlerId, enabled: bool) -> bool {
+        let handlers = self.world.read().await.handlers_of(id);
+        match handlers {
+            Some(handlers) => handlers.set_enabled(id, enabled),
+            None => false,
+        }
+    }
+
+    pub async fn handler_ids<T: Component>(&self) -> Vec<HandlerId> {
+        let event = self.get_event::<T>().await;
+        match event {
+            Some(event) => event.handler_ids(),
+            None => Vec::new(),
+        }
+    }
 }
 
 impl HandlerParam for WorldHandle {
@@ -134,8 +391,8 @@ impl HandlerParam for WorldHandle {
 
     async fn init_state(_world: WorldHandle) -> Self::State {}
 
-    async fn fetch(world: WorldHandle, _: &mut ()) -> Self::Item {
-        world.clone()
+    async fn fetch(world: WorldHandle, _: &mut ()) -> Option<Self::Item> {
+        Some(world.clone())
     }
 
     async fn can_run(_world: WorldHandle, _: &()) -> bool {
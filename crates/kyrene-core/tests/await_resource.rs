@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use kyrene_core::prelude::*;
+
+#[derive(Debug)]
+struct Device(u32);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn waiters_wake_when_the_resource_is_inserted() {
+    let world = World::new().into_world_handle();
+
+    let waiters: Vec<_> = (0..4)
+        .map(|_| {
+            let world = world.clone();
+            tokio::spawn(async move { world.await_resource::<Device>(None).await.unwrap().0 })
+        })
+        .collect();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    world.insert_resource(Device(5)).await;
+
+    for waiter in waiters {
+        assert_eq!(waiter.await.unwrap(), 5);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn waiting_times_out_with_an_error() {
+    let world = World::new().into_world_handle();
+
+    let error = world
+        .await_resource_mut::<Device>(Some(Duration::from_millis(20)))
+        .await
+        .unwrap_err();
+    assert_eq!(error.timeout, Duration::from_millis(20));
+    assert!(error.type_name.ends_with("Device"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn existing_resources_are_returned_immediately() {
+    let mut world = World::new();
+    world.insert_resource(Device(1)).await;
+
+    world
+        .await_resource_mut::<Device>(Some(Duration::ZERO))
+        .await
+        .unwrap()
+        .0 += 1;
+    assert_eq!(world.await_resource::<Device>(None).await.unwrap().0, 2);
+
+    let world = world.into_world_handle();
+    assert_eq!(
+        world
+            .await_resource::<Device>(Some(Duration::from_millis(20)))
+            .await
+            .unwrap()
+            .0,
+        2
+    );
+}
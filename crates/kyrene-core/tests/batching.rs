@@ -0,0 +1,141 @@
+mod common;
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use kyrene_core::{
+    handler::{Res, ResMut},
+    handler_graph::HandlerGraphProblem,
+    prelude::*,
+    query::Query,
+};
+
+struct Position;
+
+/// Tracks how many handlers were running at once.
+struct Overlap {
+    running: AtomicUsize,
+    most: AtomicUsize,
+    finished: AtomicUsize,
+}
+
+impl Overlap {
+    const fn new() -> Self {
+        Self {
+            running: AtomicUsize::new(0),
+            most: AtomicUsize::new(0),
+            finished: AtomicUsize::new(0),
+        }
+    }
+
+    async fn run(&self) {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.most.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        self.finished.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn wait_for(&self, handlers: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while self.finished.load(Ordering::SeqCst) < handlers {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+}
+
+struct Move;
+
+static MOVERS: Overlap = Overlap::new();
+
+async fn move_a(_event: Event<Move>, _positions: Query<&mut Position>) {
+    MOVERS.run().await;
+}
+
+async fn move_b(_event: Event<Move>, _positions: Query<&mut Position>) {
+    MOVERS.run().await;
+}
+
+#[test]
+fn conflicting_handlers_never_overlap_even_when_detached() {
+    let mut world = World::new();
+    world.add_event::<Move>();
+    world.add_event_handler(move_a);
+    world.add_event_handler(move_b);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.fire_event(Move, false).await;
+        MOVERS.wait_for(2).await;
+        assert_eq!(MOVERS.most.load(Ordering::SeqCst), 1);
+    });
+}
+
+struct Draw;
+
+static DRAWERS: Overlap = Overlap::new();
+
+async fn draw_a(_event: Event<Draw>, _positions: Query<&Position>) {
+    DRAWERS.run().await;
+}
+
+async fn draw_b(_event: Event<Draw>, _positions: Query<&Position>) {
+    DRAWERS.run().await;
+}
+
+#[test]
+fn readers_run_together() {
+    let mut world = World::new();
+    world.add_event::<Draw>();
+    world.add_event_handler(draw_a);
+    world.add_event_handler(draw_b);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.fire_event(Draw, true).await;
+        assert_eq!(DRAWERS.finished.load(Ordering::SeqCst), 2);
+        assert_eq!(DRAWERS.most.load(Ordering::SeqCst), 2);
+    });
+}
+
+struct Score;
+
+struct Tally;
+
+async fn tally(_event: Event<Tally>, _read: Query<&Position>, _write: Query<&mut Position>) {}
+
+async fn double_borrow(_event: Event<Tally>, _score: Res<Score>, _score_mut: ResMut<Score>) {}
+
+#[test]
+fn conflicting_params_are_reported_instead_of_panicking() {
+    let mut world = World::new();
+    world.add_event::<Tally>();
+    world.add_event_handler(tally);
+    world.add_event_handler(double_borrow);
+
+    common::runtime().block_on(async move {
+        let report = world.validate_handlers().await;
+        let conflicting: Vec<_> = report
+            .problems
+            .iter()
+            .filter_map(|problem| match problem {
+                HandlerGraphProblem::ConflictingParams { handler, .. } => Some(*handler),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(conflicting.len(), 2);
+        assert!(conflicting.iter().any(|handler| handler.ends_with("tally")));
+        assert!(conflicting
+            .iter()
+            .any(|handler| handler.ends_with("double_borrow")));
+
+        // the query-only handler still runs, since it never holds both borrows
+        let world = world.into_world_handle();
+        assert_eq!(world.fire_event(Tally, true).await, 2);
+    });
+}
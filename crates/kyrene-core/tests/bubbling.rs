@@ -0,0 +1,95 @@
+use kyrene_core::{handler::ResMut, prelude::*};
+
+struct Clicked;
+
+#[derive(Debug, Default)]
+struct Path(Vec<(&'static str, Entity)>);
+
+struct StopAt(Entity);
+
+async fn on_any_click(event: Event<Clicked>, mut path: ResMut<Path>) {
+    path.0.push(("global", event.current_target().unwrap()));
+}
+
+async fn on_click(event: Event<Clicked>, world: WorldHandle) {
+    let current = event.current_target().unwrap();
+    world
+        .get_resource_mut::<Path>()
+        .await
+        .unwrap()
+        .0
+        .push(("observer", current));
+    if world
+        .get_resource::<StopAt>()
+        .await
+        .is_some_and(|stop| stop.0 == current)
+    {
+        event.stop_propagation();
+    }
+}
+
+/// A root with a child, which has a child of its own. Each one observes clicks.
+async fn clickable_tree(world: &WorldHandle) -> [Entity; 3] {
+    let root = world.entity().await;
+    let middle = world.entity().await;
+    let leaf = world.entity().await;
+    world.add_child(root, middle).await.unwrap();
+    world.add_child(middle, leaf).await.unwrap();
+    for entity in [root, middle, leaf] {
+        world.observe(entity, on_click).await;
+    }
+    [root, middle, leaf]
+}
+
+async fn click(world: &WorldHandle, target: Entity) -> Vec<(&'static str, Entity)> {
+    world.insert_resource(Path::default()).await;
+    world.fire_entity_event(target, Clicked).await;
+    let mut path = world.get_resource::<Path>().await.unwrap().0.clone();
+    // handlers for the same entity run in whatever order they finish
+    path.sort_by_key(|&(kind, _)| kind == "observer");
+    path
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn global_handlers_run_once_at_the_target() {
+    let world = World::new().into_world_handle();
+    world.add_event_handler(on_any_click).await;
+    let [root, middle, leaf] = clickable_tree(&world).await;
+
+    assert_eq!(
+        click(&world, leaf).await,
+        [
+            ("global", leaf),
+            ("observer", leaf),
+            ("observer", middle),
+            ("observer", root),
+        ]
+    );
+    assert_eq!(
+        click(&world, middle).await,
+        [("global", middle), ("observer", middle), ("observer", root)]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handlers_can_stop_propagation() {
+    let world = World::new().into_world_handle();
+    world.add_event_handler(on_any_click).await;
+    let [_root, middle, leaf] = clickable_tree(&world).await;
+    world.insert_resource(StopAt(middle)).await;
+
+    assert_eq!(
+        click(&world, leaf).await,
+        [("global", leaf), ("observer", leaf), ("observer", middle)]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn observers_only_see_events_fired_at_entities() {
+    let world = World::new().into_world_handle();
+    clickable_tree(&world).await;
+    world.insert_resource(Path::default()).await;
+
+    world.fire_event(Clicked, true).await;
+    assert!(world.get_resource::<Path>().await.unwrap().0.is_empty());
+}
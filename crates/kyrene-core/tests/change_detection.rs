@@ -0,0 +1,79 @@
+mod common;
+
+use kyrene_core::{
+    handler::ResMut,
+    prelude::*,
+    query::{Added, Changed, Query},
+};
+
+#[derive(Debug, PartialEq)]
+struct Health(u32);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn changed_and_added_are_relative_to_last_run() {
+    let world = World::new().into_world_handle();
+    let entity = world.spawn((Health(10),)).await;
+
+    let query: Query<Entity, Changed<Health>> = world.query_filtered().await;
+    assert_eq!(query.iter().count().await, 1);
+
+    let last_run = world.change_tick().await.increment();
+    let query: Query<Entity, Changed<Health>> = Query::new_since(world.clone(), last_run).await;
+    assert_eq!(query.iter().count().await, 0);
+
+    world.get_mut::<Health>(entity).await.unwrap().0 = 5;
+    let query: Query<Entity, Changed<Health>> = Query::new_since(world.clone(), last_run).await;
+    assert_eq!(query.iter().count().await, 1);
+    let query: Query<Entity, Added<Health>> = Query::new_since(world.clone(), last_run).await;
+    assert_eq!(query.iter().count().await, 0);
+
+    let query: Query<&Health> = Query::new_since(world.clone(), last_run).await;
+    let health = query.get(entity).await.unwrap();
+    assert!(health.is_changed());
+    assert!(!health.is_added());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reading_through_mut_is_not_a_change() {
+    let world = World::new().into_world_handle();
+    let entity = world.spawn((Health(10),)).await;
+    let last_run = world.change_tick().await.increment();
+
+    assert_eq!(world.get_mut::<Health>(entity).await.unwrap().0, 10);
+    let query: Query<Entity, Changed<Health>> = Query::new_since(world.clone(), last_run).await;
+    assert_eq!(query.iter().count().await, 0);
+}
+
+struct Frame;
+
+#[derive(Default)]
+struct Seen(Vec<usize>);
+
+async fn count_added(
+    _event: Event<Frame>,
+    query: Query<Entity, Added<Health>>,
+    mut seen: ResMut<Seen>,
+) {
+    seen.0.push(query.iter().count().await);
+}
+
+#[test]
+fn each_handler_remembers_its_last_run() {
+    let mut world = World::new();
+    world.add_event::<Frame>();
+    world.add_event_handler(count_added);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.insert_resource(Seen::default()).await;
+        world.spawn((Health(0),)).await;
+        world.spawn((Health(1),)).await;
+
+        world.fire_event(Frame, true).await;
+        world.fire_event(Frame, true).await;
+        world.spawn((Health(2),)).await;
+        world.fire_event(Frame, true).await;
+
+        assert_eq!(world.get_resource::<Seen>().await.unwrap().0, [2, 0, 1]);
+    });
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use kyrene_core::{prelude::*, query::Query, storage::StorageType};
+
+#[derive(Debug, PartialEq)]
+struct Position(u32);
+
+#[derive(Debug, PartialEq)]
+struct Velocity(u32);
+
+async fn spawn_columns(world: &WorldHandle, count: u32) -> Vec<Entity> {
+    world
+        .set_storage_type::<Position>(StorageType::Column)
+        .await;
+    world
+        .set_storage_type::<Velocity>(StorageType::Column)
+        .await;
+    let mut entities = Vec::new();
+    for i in 0..count {
+        entities.push(world.spawn((Position(i), Velocity(i))).await);
+    }
+    entities
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn column_query_reads_and_writes() {
+    let world = World::new().into_world_handle();
+    let entities = spawn_columns(&world, 100).await;
+
+    let query: Query<(Entity, &Position, &mut Velocity)> = world.query().await;
+    let mut iter = query.iter();
+    while let Some((entity, position, mut velocity)) = iter.next().await {
+        assert_eq!(entities[position.0 as usize], entity);
+        velocity.0 += position.0;
+    }
+    drop(iter);
+    drop(query);
+
+    assert_eq!(world.get::<Velocity>(entities[40]).await.unwrap().0, 80);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn column_query_holds_many_muts_at_once() {
+    let world = World::new().into_world_handle();
+    spawn_columns(&world, 50).await;
+
+    let query: Query<&mut Velocity> = world.query().await;
+    let all = tokio::time::timeout(Duration::from_secs(5), query.iter().collect::<Vec<_>>())
+        .await
+        .expect("collecting every `Mut` of a column shouldn't deadlock");
+    assert_eq!(all.len(), 50);
+
+    let mut all = all;
+    for velocity in &mut all {
+        velocity.0 = 7;
+    }
+    drop(all);
+    drop(query);
+
+    let query: Query<&Velocity> = world.query().await;
+    assert!(
+        query
+            .iter()
+            .all(|velocity| async move { velocity.0 == 7 })
+            .await
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn switching_storage_keeps_components() {
+    let world = World::new().into_world_handle();
+    let entities = spawn_columns(&world, 10).await;
+
+    world
+        .set_storage_type::<Position>(StorageType::PerEntity)
+        .await;
+    assert_eq!(world.get::<Position>(entities[5]).await.unwrap().0, 5);
+
+    assert_eq!(
+        world.remove::<Velocity>(entities[5]).await,
+        Some(Velocity(5))
+    );
+    // the last entity was swapped into the removed one's place
+    assert_eq!(world.get::<Velocity>(entities[9]).await.unwrap().0, 9);
+    assert!(!world.has::<Velocity>(entities[5]).await);
+
+    assert!(world.despawn(entities[9]).await);
+    let query: Query<&Velocity> = world.query().await;
+    assert_eq!(query.iter().count().await, 8);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use std::time::Duration;
+
+use kyrene_core::{commands::Commands, prelude::*, query::Query};
+
+#[derive(Debug, PartialEq)]
+struct Bullet(u32);
+
+struct Spawned(Entity);
+
+struct Fire;
+
+async fn spawn_bullet(_event: Event<Fire>, commands: Commands) {
+    let bullet = commands.spawn((Bullet(7),)).await;
+    commands.insert_resource(Spawned(bullet)).await;
+}
+
+async fn despawn_bullet(_event: Event<Fire>, commands: Commands, world: WorldHandle) {
+    // the previous batch's commands were applied before this one started
+    let bullet = world.get_resource::<Spawned>().await.unwrap().0;
+    assert_eq!(world.get::<Bullet>(bullet).await.unwrap().0, 7);
+    commands.despawn(bullet).await;
+    commands.spawn((Bullet(9),)).await;
+}
+
+#[test]
+fn commands_apply_after_each_batch() {
+    let mut world = World::new();
+    world.add_event::<Fire>();
+    world.add_event_handler(spawn_bullet);
+    world.add_event_handler(despawn_bullet.after(spawn_bullet));
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.fire_event(Fire, true).await;
+
+        let first = world.get_resource::<Spawned>().await.unwrap().0;
+        assert!(!world.is_alive(first).await);
+        let query: Query<&Bullet> = world.query().await;
+        let bullets: Vec<u32> = query.iter().map(|bullet| bullet.0).collect().await;
+        assert_eq!(bullets, [9]);
+    });
+}
+
+#[test]
+fn detached_fires_apply_their_own_commands() {
+    let mut world = World::new();
+    world.add_event::<Fire>();
+    world.add_event_handler(spawn_bullet);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.fire_event(Fire, false).await;
+
+        // nothing else is fired or awaited, so only the fire's own sync point can apply them
+        let spawned = world
+            .await_resource::<Spawned>(Some(Duration::from_secs(5)))
+            .await
+            .expect("the detached batch's commands were never applied")
+            .0;
+        assert_eq!(world.get::<Bullet>(spawned).await.unwrap().0, 7);
+    });
+}
+
+struct Ping;
+
+async fn ping(_event: Event<Ping>) {}
+
+#[test]
+fn a_fire_does_not_apply_commands_it_did_not_record() {
+    let mut world = World::new();
+    world.add_event::<Ping>();
+    world.add_event_handler(ping);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        let commands = Commands::new(world.clone()).await;
+        let bullet = commands.spawn((Bullet(1),)).await;
+
+        world.fire_event(Ping, true).await;
+        assert!(!world.has::<Bullet>(bullet).await);
+
+        world.apply_commands().await;
+        assert_eq!(world.get::<Bullet>(bullet).await.unwrap().0, 1);
+    });
+}
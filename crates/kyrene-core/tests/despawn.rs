@@ -0,0 +1,50 @@
+use kyrene_core::prelude::*;
+
+#[derive(Debug, PartialEq)]
+struct Position(u32);
+
+#[derive(Debug, PartialEq)]
+struct Velocity(u32);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn despawn_removes_every_component() {
+    let mut world = World::new();
+    let entity = world.spawn((Position(1), Velocity(2))).await;
+    let other = world.spawn((Position(3),)).await;
+
+    assert!(world.despawn(entity).await);
+    assert!(!world.is_alive(entity));
+    assert!(world.get::<Position>(entity).await.is_none());
+    assert!(!world.has::<Velocity>(entity));
+    assert_eq!(
+        world.entities_with::<Position>().collect::<Vec<_>>(),
+        [other]
+    );
+    assert_eq!(world.get::<Position>(other).await.unwrap().0, 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn despawn_twice_returns_false() {
+    let world = World::new().into_world_handle();
+    let entity = world.spawn((Position(0),)).await;
+
+    assert!(world.despawn(entity).await);
+    assert!(!world.despawn(entity).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reused_id_gets_new_generation() {
+    let world = World::new().into_world_handle();
+    let stale = world.spawn((Position(0),)).await;
+    world.despawn(stale).await;
+
+    let fresh = world.spawn((Velocity(1),)).await;
+    assert_eq!(fresh.id(), stale.id());
+    assert_ne!(fresh.generation(), stale.generation());
+
+    // the stale handle can't see the new entity's components
+    assert!(world.get::<Velocity>(stale).await.is_none());
+    assert!(!world.has::<Velocity>(stale).await);
+    assert!(!world.despawn(stale).await);
+    assert!(world.is_alive(fresh).await);
+}
@@ -0,0 +1,112 @@
+use kyrene_core::{event_queue::EventQueue, handler::ResMut, prelude::*, runner::RunConfig};
+use pollster::block_on;
+
+#[derive(Debug)]
+struct Hit(u64);
+
+#[derive(Debug, Default)]
+struct Seen(Vec<u64>);
+
+#[derive(Debug, Default)]
+struct SeenEveryOtherTick(Vec<u64>);
+
+#[derive(Debug, Default)]
+struct SeenEveryThirdTick(Vec<u64>);
+
+async fn send_hits(event: Event<WorldTick>, mut hits: EventWriter<Hit>) {
+    hits.send_batch([Hit(event.tick * 10), Hit(event.tick * 10 + 1)]);
+}
+
+async fn read_hits(_event: Event<WorldTick>, mut hits: EventReader<Hit>, mut seen: ResMut<Seen>) {
+    seen.0.extend(hits.read().map(|hit| hit.0));
+    assert!(hits.is_empty());
+}
+
+async fn read_hits_every_other_tick(
+    event: Event<WorldTick>,
+    mut hits: EventReader<Hit>,
+    mut seen: ResMut<SeenEveryOtherTick>,
+) {
+    if event.tick.is_multiple_of(2) {
+        assert_eq!(hits.len(), 4);
+        seen.0.extend(hits.read().map(|hit| hit.0));
+    }
+}
+
+async fn read_hits_every_third_tick(
+    event: Event<WorldTick>,
+    mut hits: EventReader<Hit>,
+    mut seen: ResMut<SeenEveryThirdTick>,
+) {
+    if event.tick.is_multiple_of(3) {
+        seen.0.extend(hits.read().map(|hit| hit.0));
+    }
+}
+
+#[test]
+fn queues_keep_events_for_two_ticks() {
+    let mut queue = EventQueue::new();
+    queue.send(1);
+    queue.send_batch([2, 3]);
+    assert_eq!(queue.len(), 3);
+
+    queue.swap();
+    queue.send(4);
+    assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+    queue.swap();
+    assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [4]);
+    queue.swap();
+    assert!(queue.is_empty());
+
+    queue.send(5);
+    queue.clear();
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn readers_see_each_event_once() {
+    let mut world = World::new();
+    world.add_event_handler(send_hits);
+    world.add_event_handler(read_hits.after(send_hits));
+    world.add_event_handler(read_hits_every_other_tick.after(send_hits));
+    world.add_event_handler(read_hits_every_third_tick.after(send_hits));
+    block_on(async {
+        world.insert_resource(Seen::default()).await;
+        world.insert_resource(SeenEveryOtherTick::default()).await;
+        world.insert_resource(SeenEveryThirdTick::default()).await;
+    });
+
+    let world = world
+        .run_headless(RunConfig::new().with_max_ticks(6))
+        .unwrap();
+
+    let seen = block_on(world.get_resource::<Seen>()).unwrap().0.clone();
+    assert_eq!(seen, [10, 11, 20, 21, 30, 31, 40, 41, 50, 51, 60, 61]);
+    let every_other = block_on(world.get_resource::<SeenEveryOtherTick>())
+        .unwrap()
+        .0
+        .clone();
+    assert_eq!(every_other, seen);
+    // reading less than every other tick misses whatever was already dropped
+    let every_third = block_on(world.get_resource::<SeenEveryThirdTick>())
+        .unwrap()
+        .0
+        .clone();
+    assert_eq!(every_third, [20, 21, 30, 31, 50, 51, 60, 61]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn readers_added_later_see_what_is_still_buffered() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Seen::default()).await;
+    world.send_event(Hit(1)).await;
+    world.update().await;
+    world.update().await;
+    world.send_event(Hit(2)).await;
+    world.send_event(Hit(3)).await;
+
+    world.add_event_handler(read_hits).await;
+    world.update().await;
+    assert_eq!(world.get_resource::<Seen>().await.unwrap().0, [2, 3]);
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use kyrene_core::{
+    error::{HandlerErrorPolicy, HandlerFailed},
+    handler::ResMut,
+    prelude::*,
+};
+
+struct Go;
+
+#[derive(Debug, Default)]
+struct Failures(Vec<(&'static str, String)>);
+
+async fn fails(_event: Event<Go>) -> Result<(), std::io::Error> {
+    Err(std::io::Error::other("nope"))
+}
+
+async fn parses(_event: Event<Go>) -> Result<(), kyrene_core::error::HandlerError> {
+    let _: u32 = "not a number".parse()?;
+    Ok(())
+}
+
+async fn panics(_event: Event<Go>) {
+    panic!("boom");
+}
+
+async fn record_failure(event: Event<HandlerFailed>, mut failures: ResMut<Failures>) {
+    assert!(event.event_type.ends_with("Go"));
+    let handler = event.handler.rsplit("::").next().unwrap();
+    failures.0.push((handler, event.error.to_string()));
+}
+
+/// `HandlerFailed` is fired without waiting on its handlers, so give them a moment.
+async fn wait_for_failures(world: &WorldHandle, count: usize) -> Vec<(&'static str, String)> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let failures = world.get_resource::<Failures>().await.unwrap().0.clone();
+            if failures.len() >= count {
+                return failures;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("handler failures were never reported")
+}
+
+fn failing_world() -> World {
+    let mut world = World::new();
+    world.add_event::<Go>();
+    world.add_event_handler(fails);
+    world.add_event_handler(parses);
+    world.add_event_handler(panics);
+    world.add_event_handler(record_failure);
+    world
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failures_and_panics_are_fired_as_events() {
+    let world = failing_world().into_world_handle();
+    world.insert_resource(Failures::default()).await;
+    world.insert_resource(HandlerErrorPolicy::Fire).await;
+
+    assert_eq!(world.fire_event(Go, true).await, 3);
+
+    let mut failures = wait_for_failures(&world, 3).await;
+    failures.sort();
+    assert_eq!(
+        failures,
+        [
+            ("fails", "nope".to_string()),
+            ("panics", "panicked: boom".to_string()),
+            ("parses", "invalid digit found in string".to_string()),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failing_handlers_can_be_disabled() {
+    let world = failing_world().into_world_handle();
+    world.insert_resource(Failures::default()).await;
+    world.insert_resource(HandlerErrorPolicy::Disable).await;
+
+    world.fire_event(Go, true).await;
+    world.insert_resource(HandlerErrorPolicy::Fire).await;
+    assert_eq!(world.fire_event(Go, true).await, 3);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(world.get_resource::<Failures>().await.unwrap().0.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn logging_keeps_handlers_running() {
+    let world = failing_world().into_world_handle();
+    world.insert_resource(Failures::default()).await;
+
+    world.fire_event(Go, true).await;
+    world.insert_resource(HandlerErrorPolicy::Fire).await;
+    world.fire_event(Go, true).await;
+
+    assert_eq!(wait_for_failures(&world, 3).await.len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic = "nope"]
+async fn the_panic_policy_panics_in_the_firing_task() {
+    let mut world = World::new();
+    world.add_event::<Go>();
+    world.add_event_handler(fails);
+    let world = world.into_world_handle();
+    world.insert_resource(HandlerErrorPolicy::Panic).await;
+
+    world.fire_event(Go, true).await;
+}
@@ -0,0 +1,139 @@
+use kyrene_core::{
+    handler::{HandlerEdge, Res, ResMut},
+    handler_graph::{HandlerGraph, HandlerGraphProblem, HandlerNode},
+    handler_set::IntoHandlerSetConfig,
+    prelude::*,
+    HandlerSet,
+};
+
+#[derive(HandlerSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum Stage {
+    First,
+    Second,
+}
+
+struct Go;
+
+struct NeverAdded;
+
+#[derive(Default)]
+struct Score(u32);
+
+async fn write(_event: Event<Go>, mut score: ResMut<Score>) {
+    score.0 += 1;
+}
+
+async fn read(_event: Event<Go>, _score: Res<Score>) {}
+
+async fn first(_event: Event<Go>) {}
+
+async fn second(_event: Event<Go>) {}
+
+async fn never_added(_event: Event<Go>) {}
+
+fn graph_world() -> World {
+    let mut world = World::new();
+    world.add_event_handler(write);
+    world.add_event_handler(read.after(write));
+    world.add_event_handler(first.in_set(Stage::First));
+    world.add_event_handler(second.in_set(Stage::Second));
+    world.configure_set::<Go>(Stage::Second.after(Stage::First));
+    world
+}
+
+fn node<'a>(graph: &'a HandlerGraph, name: &str) -> &'a HandlerNode {
+    graph
+        .nodes
+        .iter()
+        .find(|node| node.name.ends_with(name))
+        .unwrap()
+}
+
+fn index_of(graph: &HandlerGraph, name: &str) -> usize {
+    node(graph, name).index
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn graphs_have_every_handler_and_constraint() {
+    let world = graph_world();
+    let graph = world.handler_graph::<Go>().await.unwrap();
+    assert!(graph.event_type.ends_with("Go"));
+    assert_eq!(graph.nodes.len(), 4);
+
+    let write = node(&graph, "::write");
+    assert!(write.enabled);
+    assert!(write.resources_read.is_empty());
+    assert!(write.resources_written[0].ends_with("Score"));
+
+    let mut edges = graph
+        .edges
+        .iter()
+        .map(|edge| (edge.from, edge.to, edge.kind))
+        .collect::<Vec<_>>();
+    edges.sort_by_key(|&(from, to, _)| (from, to));
+    let mut expected = vec![
+        (
+            index_of(&graph, "::write"),
+            index_of(&graph, "::read"),
+            HandlerEdge::Explicit,
+        ),
+        (
+            index_of(&graph, "::first"),
+            index_of(&graph, "::second"),
+            HandlerEdge::Set,
+        ),
+    ];
+    expected.sort_by_key(|&(from, to, _)| (from, to));
+    assert_eq!(edges, expected);
+
+    assert!(world.handler_graph::<NeverAdded>().await.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn graphs_render_as_dot_and_json() {
+    let world = graph_world();
+    let graph = world.handler_graph::<Go>().await.unwrap();
+    let read = index_of(&graph, "::read");
+    let write = index_of(&graph, "::write");
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph \""));
+    assert!(dot.contains(&format!("h{write} -> h{read} [style=solid];")));
+    assert_eq!(dot.matches("[style=dashed]").count(), 1);
+    assert!(dot.contains("\\nreads: "));
+    assert!(dot.trim_end().ends_with('}'));
+
+    let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 4);
+    let kinds = json["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|edge| edge["kind"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert!(kinds.contains(&"explicit") && kinds.contains(&"set"));
+
+    let all = world.dump_all_handler_graphs().await;
+    let events = all
+        .graphs
+        .iter()
+        .map(|graph| graph.event_type)
+        .collect::<Vec<_>>();
+    assert!(events.is_sorted());
+    assert!(events.iter().any(|event| event.ends_with("::Go")));
+    let json: serde_json::Value = serde_json::from_str(&all.to_json()).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), all.graphs.len());
+    assert_eq!(all.to_dot().matches("digraph").count(), all.graphs.len());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn problems_name_types_rather_than_ids() {
+    let mut world = World::new();
+    world.add_event_handler(first.before(never_added));
+    let report = world.validate_handlers().await;
+
+    let [HandlerGraphProblem::MissingDependency { dependency, .. }] = &report.problems[..] else {
+        panic!("expected one missing dependency: {report}");
+    };
+    assert!(dependency.ends_with("never_added"), "{dependency}");
+}
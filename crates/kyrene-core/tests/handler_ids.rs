@@ -0,0 +1,66 @@
+use kyrene_core::{
+    handler::{HandlerId, ResMut},
+    prelude::*,
+};
+
+struct Go;
+
+#[derive(Debug, Default)]
+struct Order(Vec<&'static str>);
+
+struct OnlyOnce(HandlerId);
+
+async fn a(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("a");
+}
+
+async fn b(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("b");
+}
+
+async fn remove_self(_event: Event<Go>, world: WorldHandle) {
+    let id = world.get_resource::<OnlyOnce>().await.unwrap().0;
+    assert!(world.remove_handler(id).await);
+}
+
+async fn fire(world: &WorldHandle) -> Vec<&'static str> {
+    world.insert_resource(Order::default()).await;
+    world.fire_event(Go, true).await;
+    let mut order = world.get_resource::<Order>().await.unwrap().0.clone();
+    order.sort();
+    order
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handlers_can_be_listed_disabled_and_removed() {
+    let mut world = World::new();
+    world.add_event::<Go>();
+    let a = world.add_event_handler(a);
+    let b = world.add_event_handler(b);
+    assert_eq!(world.handler_ids::<Go>(), [a, b]);
+    assert!(world.handler_ids::<WorldTick>().is_empty());
+
+    assert!(world.set_handler_enabled(b, false));
+    let world = world.into_world_handle();
+    assert_eq!(fire(&world).await, ["a"]);
+
+    assert!(world.set_handler_enabled(b, true).await);
+    assert!(world.remove_handler(a).await);
+    assert!(!world.remove_handler(a).await);
+    assert!(!world.set_handler_enabled(a, true).await);
+    assert_eq!(world.handler_ids::<Go>().await, [b]);
+    assert_eq!(fire(&world).await, ["b"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handlers_can_remove_themselves() {
+    let world = World::new().into_world_handle();
+    world.add_event_handler(a).await;
+    let id = world.add_event_handler(remove_self).await;
+    world.insert_resource(OnlyOnce(id)).await;
+    world.insert_resource(Order::default()).await;
+
+    assert_eq!(world.fire_event(Go, true).await, 2);
+    assert_eq!(world.handler_ids::<Go>().await.len(), 1);
+    assert_eq!(world.fire_event(Go, true).await, 1);
+}
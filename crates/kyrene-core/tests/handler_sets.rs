@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use kyrene_core::{
+    handler::{Res, ResMut},
+    handler_set::IntoHandlerSetConfig,
+    prelude::*,
+    HandlerSet,
+};
+
+#[derive(HandlerSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum Stage {
+    First,
+    Second,
+}
+
+#[derive(HandlerSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct Late;
+
+struct Go;
+
+#[derive(Debug, Default)]
+struct Order(Vec<&'static str>);
+
+struct Enabled(bool);
+
+async fn a(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("a");
+}
+
+async fn b(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("b");
+}
+
+async fn c(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("c");
+}
+
+async fn d(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("d");
+}
+
+async fn is_enabled(enabled: Res<Enabled>) -> bool {
+    enabled.0
+}
+
+static EVALUATIONS: AtomicUsize = AtomicUsize::new(0);
+
+async fn counted() -> bool {
+    EVALUATIONS.fetch_add(1, Ordering::SeqCst);
+    true
+}
+
+fn staged_world() -> WorldHandle {
+    let mut world = World::new();
+    world.add_event::<Go>();
+    world.add_event_handler(b.in_set(Stage::Second));
+    world.add_event_handler(a.in_set(Stage::First));
+    world.add_event_handler(c.after_set(Stage::Second).run_if(is_enabled));
+    world.add_event_handler(d.in_set(Late));
+    world.configure_set::<Go>(Stage::Second.after(Stage::First));
+    world.configure_set::<Go>(Late.after(Stage::Second));
+    world.into_world_handle()
+}
+
+async fn fire(world: &WorldHandle) -> Vec<&'static str> {
+    world.insert_resource(Order::default()).await;
+    world.fire_event(Go, true).await;
+    world.get_resource::<Order>().await.unwrap().0.clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sets_order_their_handlers() {
+    let world = staged_world();
+    world.insert_resource(Enabled(true)).await;
+
+    let order = fire(&world).await;
+    assert_eq!(order[..2], ["a", "b"]);
+    assert_eq!(order.len(), 4);
+    assert!(order[2..].contains(&"c") && order[2..].contains(&"d"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conditions_skip_handlers_and_sets() {
+    let world = staged_world();
+
+    // the condition's own param is missing, so it isn't met
+    assert_eq!(fire(&world).await, ["a", "b", "d"]);
+
+    world.insert_resource(Enabled(false)).await;
+    world
+        .get_event::<Go>()
+        .await
+        .unwrap()
+        .configure_set(Stage::First.run_if(|| async { false }));
+    assert_eq!(fire(&world).await, ["b", "d"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn set_conditions_are_checked_once_per_fire() {
+    let world = World::new().into_world_handle();
+    let go = world.add_event::<Go>().await;
+    world.add_event_handler(a.in_set(Late)).await;
+    world.add_event_handler(b.in_set(Late)).await;
+    world.add_event_handler(c.in_set(Late)).await;
+    go.configure_set(Late.run_if(counted));
+
+    let order = fire(&world).await;
+    assert_eq!(order.len(), 3);
+    assert_eq!(EVALUATIONS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,73 @@
+use kyrene_core::{handler::ResMut, handler_graph::HandlerGraphProblem, prelude::*};
+
+struct Go;
+
+#[derive(Debug, Default)]
+struct Order(Vec<&'static str>);
+
+async fn a(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("a");
+}
+
+async fn b(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("b");
+}
+
+async fn c(_event: Event<Go>, mut order: ResMut<Order>) {
+    order.0.push("c");
+}
+
+async fn never_added(_event: Event<Go>) {}
+
+fn short_name(name: &str) -> &str {
+    name.rsplit("::").next().unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handlers_can_be_ordered_against_later_ones() {
+    let mut world = World::new();
+    world.add_event_handler(b.after(a));
+    world.add_event_handler(a);
+    world.insert_resource(Order::default()).await;
+
+    let report = world.validate_handlers().await;
+    assert!(report.is_ok(), "{report}");
+    assert_eq!(report.to_string(), "no problems with handler ordering");
+
+    let world = world.into_world_handle();
+    world.fire_event(Go, true).await;
+    assert_eq!(world.get_resource::<Order>().await.unwrap().0, ["a", "b"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cycles_and_missing_dependencies_are_reported() {
+    let mut world = World::new();
+    world.add_event_handler(a.after(c));
+    world.add_event_handler(c.after(a));
+    world.add_event_handler(b.before(never_added));
+    world.insert_resource(Order::default()).await;
+
+    let report = world.validate_handlers().await;
+    assert!(!report.is_ok());
+
+    let cycles = report.cycles().collect::<Vec<_>>();
+    assert_eq!(cycles.len(), 1);
+    let mut cycle = cycles[0]
+        .iter()
+        .map(|name| short_name(name))
+        .collect::<Vec<_>>();
+    cycle.sort();
+    assert_eq!(cycle, ["a", "c"]);
+
+    let missing = report.missing_dependencies().collect::<Vec<_>>();
+    let [HandlerGraphProblem::MissingDependency { handler, .. }] = missing[..] else {
+        panic!("expected one missing dependency, got {missing:?}");
+    };
+    assert_eq!(short_name(handler), "b");
+    assert!(report.to_string().contains("cycle"), "{report}");
+
+    // the cycle never runs, the missing constraint is ignored
+    let world = world.into_world_handle();
+    world.fire_event(Go, true).await;
+    assert_eq!(world.get_resource::<Order>().await.unwrap().0, ["b"]);
+}
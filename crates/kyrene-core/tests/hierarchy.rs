@@ -0,0 +1,85 @@
+use kyrene_core::{
+    hierarchy::{Children, HierarchyError, Parent},
+    prelude::*,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn children_and_ancestors() {
+    let world = World::new().into_world_handle();
+    let root = world.entity().await;
+    let a = world.entity().await;
+    let b = world.entity().await;
+    let c = world.entity().await;
+
+    world.add_child(root, a).await.unwrap();
+    world.add_child(root, b).await.unwrap();
+    world.add_child(a, c).await.unwrap();
+
+    assert_eq!(world.children_of(root).await, [a, b]);
+    assert_eq!(world.ancestors(c).collect::<Vec<_>>().await, [a, root]);
+    assert_eq!(world.descendants(root).collect::<Vec<_>>().await, [a, b, c]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reparenting_keeps_both_sides_in_sync() {
+    let world = World::new().into_world_handle();
+    let a = world.entity().await;
+    let b = world.entity().await;
+    let child = world.entity().await;
+
+    world.add_child(a, child).await.unwrap();
+    world.set_parent(child, Some(b)).await.unwrap();
+    assert!(!world.has::<Children>(a).await);
+    assert_eq!(world.parent_of(child).await, Some(b));
+
+    assert!(world.remove_child(b, child).await);
+    assert!(!world.remove_child(b, child).await);
+    assert!(!world.has::<Parent>(child).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cycles_and_dead_entities_are_rejected() {
+    let world = World::new().into_world_handle();
+    let parent = world.entity().await;
+    let child = world.entity().await;
+    world.add_child(parent, child).await.unwrap();
+
+    assert_eq!(
+        world.add_child(child, parent).await,
+        Err(HierarchyError::Cycle {
+            parent: child,
+            child: parent
+        })
+    );
+    assert!(world.add_child(child, child).await.is_err());
+    assert_eq!(world.parent_of(parent).await, None);
+
+    let dead = world.entity().await;
+    world.despawn(dead).await;
+    assert_eq!(
+        world.add_child(parent, dead).await,
+        Err(HierarchyError::DeadEntity(dead))
+    );
+    assert_eq!(world.children_of(parent).await, [child]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn despawning_detaches_and_recursive_despawn_takes_descendants() {
+    let world = World::new().into_world_handle();
+    let root = world.entity().await;
+    let a = world.entity().await;
+    let b = world.entity().await;
+    let c = world.entity().await;
+    world.add_child(root, a).await.unwrap();
+    world.add_child(root, b).await.unwrap();
+    world.add_child(b, c).await.unwrap();
+
+    world.despawn(b).await;
+    assert_eq!(world.children_of(root).await, [a]);
+    assert_eq!(world.parent_of(c).await, None);
+
+    world.add_child(a, c).await.unwrap();
+    assert!(world.despawn_recursive(root).await);
+    assert!(!world.is_alive(a).await);
+    assert!(!world.is_alive(c).await);
+}
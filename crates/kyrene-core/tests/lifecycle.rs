@@ -0,0 +1,66 @@
+mod common;
+
+use kyrene_core::{
+    component::{ComponentAdded, ComponentInserted, ComponentRemoved},
+    handler::ResMut,
+    prelude::*,
+};
+
+struct Sprite;
+
+#[derive(Debug, Default, PartialEq)]
+struct Log(Vec<(&'static str, Entity)>);
+
+async fn on_add(event: Event<ComponentAdded<Sprite>>, mut log: ResMut<Log>) {
+    log.0.push(("added", event.entity));
+}
+
+async fn on_insert(event: Event<ComponentInserted<Sprite>>, mut log: ResMut<Log>) {
+    log.0.push(("inserted", event.entity));
+}
+
+async fn on_remove(event: Event<ComponentRemoved<Sprite>>, mut log: ResMut<Log>) {
+    log.0.push(("removed", event.entity));
+}
+
+#[test]
+fn lifecycle_events_follow_structural_changes() {
+    let mut world = World::new();
+    world.add_event_handler(on_add);
+    world.add_event_handler(on_insert);
+    world.add_event_handler(on_remove);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.insert_resource(Log::default()).await;
+
+        let entity = world.spawn((Sprite,)).await;
+        world.insert(entity, Sprite).await;
+        world.remove::<Sprite>(entity).await;
+        world.insert(entity, Sprite).await;
+        world.despawn(entity).await;
+
+        assert_eq!(
+            world.get_resource::<Log>().await.unwrap().0,
+            [
+                ("added", entity),
+                ("inserted", entity),
+                ("inserted", entity),
+                ("removed", entity),
+                ("added", entity),
+                ("inserted", entity),
+                ("removed", entity),
+            ]
+        );
+    });
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unobserved_components_register_no_events() {
+    let world = World::new().into_world_handle();
+    let entity = world.spawn((Sprite,)).await;
+    world.despawn(entity).await;
+
+    assert!(!world.has_event::<ComponentAdded<Sprite>>().await);
+    assert!(!world.has_event::<ComponentRemoved<Sprite>>().await);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::time::Duration;
+
+use kyrene_core::{diagnostics::set_lock_diagnostics, prelude::*};
+
+struct Left;
+
+struct Right;
+
+struct Go;
+
+async fn left_then_right(_event: Event<Go>, world: WorldHandle) {
+    let _left = world.get_resource_mut::<Left>().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let _right = world.get_resource_mut::<Right>().await.unwrap();
+}
+
+async fn right_then_left(_event: Event<Go>, world: WorldHandle) {
+    let _right = world.get_resource_mut::<Right>().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let _left = world.get_resource_mut::<Left>().await.unwrap();
+}
+
+#[test]
+fn wait_for_cycles_are_reported() {
+    let (logs, _subscriber) = common::capture_logs();
+    set_lock_diagnostics(true);
+
+    let mut world = World::new();
+    world.add_event::<Go>();
+    world.add_event_handler(left_then_right);
+    world.add_event_handler(right_then_left);
+    let world = world.into_world_handle();
+
+    // a single thread, so every handler logs through the subscriber set above
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            world.insert_resource(Left).await;
+            world.insert_resource(Right).await;
+
+            // the handlers never finish, so don't wait on them
+            world.fire_event(Go, false).await;
+            tokio::time::timeout(Duration::from_secs(5), async {
+                while !logs.contents().contains("Deadlock detected") {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("the deadlock was never reported");
+        });
+
+    let report = logs.contents();
+    assert!(report.contains("Deadlock detected between 2 handler(s)"));
+    assert!(
+        report.contains("left_then_right` is waiting to write resource `lock_diagnostics::Right`")
+    );
+    assert!(
+        report.contains("right_then_left` is waiting to write resource `lock_diagnostics::Left`")
+    );
+}
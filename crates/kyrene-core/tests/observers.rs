@@ -0,0 +1,73 @@
+use kyrene_core::{handler::ResMut, prelude::*};
+
+struct Poked;
+
+#[derive(Debug, Default)]
+struct Pokes(Vec<Entity>);
+
+async fn record_poke(event: Event<Poked>, mut pokes: ResMut<Pokes>) {
+    pokes.0.push(event.current_target().unwrap());
+}
+
+async fn despawn_when_poked(event: Event<Poked>, world: WorldHandle) {
+    assert!(world.despawn(event.current_target().unwrap()).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn observers_are_removed_on_despawn() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Pokes::default()).await;
+    let entity = world.entity().await;
+    let other = world.entity().await;
+    world.observe(entity, record_poke).await;
+    let kept = world.observe(other, record_poke).await;
+
+    world.fire_entity_event(entity, Poked).await;
+    assert!(world.despawn(entity).await);
+    assert_eq!(world.handler_ids::<Poked>().await, [kept]);
+
+    // whatever reuses its slot starts with no observers
+    let reused = world.entity().await;
+    world.fire_entity_event(reused, Poked).await;
+    world.fire_entity_event(other, Poked).await;
+    assert_eq!(
+        world.get_resource::<Pokes>().await.unwrap().0,
+        [entity, other]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn observing_a_dead_entity_adds_nothing() {
+    let world = World::new().into_world_handle();
+    let entity = world.entity().await;
+    world.despawn(entity).await;
+
+    world.observe(entity, record_poke).await;
+    assert!(world.handler_ids::<Poked>().await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn observers_can_despawn_their_own_entity() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Pokes::default()).await;
+    let entity = world.entity().await;
+    world.observe(entity, despawn_when_poked).await;
+    world.observe(entity, record_poke).await;
+
+    world.fire_entity_event(entity, Poked).await;
+    assert!(!world.is_alive(entity).await);
+    assert!(world.handler_ids::<Poked>().await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn despawning_recursively_removes_descendants_observers() {
+    let world = World::new().into_world_handle();
+    let root = world.entity().await;
+    let child = world.entity().await;
+    world.add_child(root, child).await.unwrap();
+    world.observe(root, record_poke).await;
+    world.observe(child, record_poke).await;
+
+    assert!(world.despawn_recursive(root).await);
+    assert!(world.handler_ids::<Poked>().await.is_empty());
+}
@@ -0,0 +1,47 @@
+use kyrene_core::{prelude::*, query::Query};
+
+#[derive(Debug, PartialEq)]
+struct Id(u32);
+
+#[derive(Debug, PartialEq)]
+struct Health(u32);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tuple_items_belong_to_the_same_entity() {
+    let world = World::new().into_world_handle();
+    let mut entities = Vec::new();
+    for i in 0..200 {
+        entities.push(world.spawn((Id(i), Health(i))).await);
+    }
+
+    let query: Query<(Entity, &Id, &mut Health)> = world.query().await;
+    let mut iter = Box::pin(query.iter());
+    let mut seen = 0;
+    while let Some((entity, id, mut health)) = iter.next().await {
+        assert_eq!(entities[id.0 as usize], entity);
+        assert_eq!(health.0, id.0);
+        health.0 += 1000;
+        seen += 1;
+    }
+    assert_eq!(seen, 200);
+    drop(iter);
+
+    for (i, entity) in entities.into_iter().enumerate() {
+        assert_eq!(
+            world.get::<Health>(entity).await.unwrap().0,
+            i as u32 + 1000
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tuple_skips_entities_missing_an_element() {
+    let world = World::new().into_world_handle();
+    let both = world.spawn((Id(0), Health(0))).await;
+    world.spawn((Id(1),)).await;
+    world.spawn((Health(2),)).await;
+
+    let query: Query<(Entity, &Id, &Health)> = world.query().await;
+    let found: Vec<_> = query.iter().map(|(entity, ..)| entity).collect().await;
+    assert_eq!(found, [both]);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use kyrene_core::{
+    handler::ResMut,
+    prelude::*,
+    query::{Has, Or, Query, With, Without},
+};
+
+#[derive(Debug, PartialEq)]
+struct Transform(u32);
+
+#[derive(Debug, PartialEq)]
+struct Sprite(u32);
+
+struct Hidden;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn without_and_with_filter_entities() {
+    let world = World::new().into_world_handle();
+    let shown = world.spawn((Transform(0),)).await;
+    let hidden = world.spawn((Transform(1), Hidden)).await;
+
+    let query: Query<Entity, Without<Hidden>> = world.query_filtered().await;
+    assert_eq!(query.iter().collect::<Vec<_>>().await, [shown]);
+
+    let query: Query<Entity, With<Hidden>> = world.query_filtered().await;
+    assert_eq!(query.iter().collect::<Vec<_>>().await, [hidden]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn or_matches_either_filter() {
+    let world = World::new().into_world_handle();
+    world.spawn((Transform(0),)).await;
+    world.spawn((Sprite(1),)).await;
+    world.spawn((Hidden,)).await;
+
+    type Drawable = Or<(With<Transform>, With<Sprite>)>;
+    let query: Query<Entity, Drawable> = world.query_filtered().await;
+    assert_eq!(query.iter().count().await, 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn option_and_has_never_filter() {
+    let world = World::new().into_world_handle();
+    let a = world.spawn((Transform(0),)).await;
+    let b = world.spawn((Transform(1), Hidden)).await;
+    let c = world.spawn((Sprite(2),)).await;
+
+    let query: Query<(Entity, Option<&Transform>, Has<Hidden>)> = world.query().await;
+    let mut found: Vec<_> = query
+        .iter()
+        .map(|(entity, transform, hidden)| (entity, transform.map(|t| t.0), hidden))
+        .collect()
+        .await;
+    found.sort();
+    assert_eq!(
+        found,
+        [(a, Some(0), false), (b, Some(1), true), (c, None, false)]
+    );
+
+    let query: Query<Option<&mut Sprite>> = world.query().await;
+    let mut iter = query.iter();
+    while let Some(sprite) = iter.next().await {
+        if let Some(mut sprite) = sprite {
+            sprite.0 += 1;
+        }
+    }
+    drop(iter);
+    assert_eq!(world.get::<Sprite>(c).await.unwrap().0, 3);
+}
+
+struct Render;
+
+#[derive(Default)]
+struct Rendered(Vec<u32>);
+
+async fn render(
+    _event: Event<Render>,
+    query: Query<&Transform, Without<Hidden>>,
+    mut rendered: ResMut<Rendered>,
+) {
+    let mut iter = query.iter();
+    while let Some(transform) = iter.next().await {
+        rendered.0.push(transform.0);
+    }
+}
+
+#[test]
+fn filtered_query_as_handler_param() {
+    let mut world = World::new();
+    world.add_event::<Render>();
+    world.add_event_handler(render);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.insert_resource(Rendered::default()).await;
+        world.spawn((Transform(1),)).await;
+        world.spawn((Transform(2), Hidden)).await;
+
+        world.fire_event(Render, true).await;
+        assert_eq!(world.get_resource::<Rendered>().await.unwrap().0, [1]);
+    });
+}
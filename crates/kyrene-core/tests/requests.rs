@@ -0,0 +1,77 @@
+use kyrene_core::{handler::ResMut, prelude::*};
+
+struct Ask(u32);
+
+impl Request for Ask {
+    type Response = u32;
+}
+
+#[derive(Default)]
+struct Asked(u32);
+
+async fn double(event: Event<Ask>, mut asked: ResMut<Asked>) -> Response<u32> {
+    asked.0 += 1;
+    Response(event.0 * 2)
+}
+
+async fn add_one(event: Event<Ask>) -> Response<u32> {
+    Response(event.0 + 1)
+}
+
+async fn only_even(event: Event<Ask>) -> Option<Response<u32>> {
+    event.0.is_multiple_of(2).then_some(Response(event.0))
+}
+
+async fn parse(_event: Event<Ask>) -> Result<Response<u32>, std::num::ParseIntError> {
+    Ok(Response("not a number".parse()?))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn requests_collect_every_response() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Asked::default()).await;
+    world.add_event_handler(double).await;
+    world.add_event_handler(only_even).await;
+    world.add_event_handler(parse).await;
+
+    let mut responses = world.request(Ask(4)).await;
+    responses.sort();
+    assert_eq!(responses, [4, 8]);
+    // neither `None` nor an error answers it
+    assert_eq!(world.request(Ask(3)).await, [6]);
+    assert_eq!(world.request_first(Ask(5)).await, Some(10));
+    assert_eq!(world.get_resource::<Asked>().await.unwrap().0, 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ordered_handlers_respond_in_order() {
+    let world = World::new().into_world_handle();
+    world.add_event_handler(add_one.after(double)).await;
+    world.add_event_handler(double).await;
+    world.insert_resource(Asked::default()).await;
+
+    assert_eq!(world.request(Ask(10)).await, [20, 11]);
+    assert_eq!(world.request_first(Ask(10)).await, Some(20));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unanswered_requests_return_nothing() {
+    let world = World::new().into_world_handle();
+    assert!(world.request(Ask(1)).await.is_empty());
+    assert_eq!(world.request_first(Ask(1)).await, None);
+
+    world.add_event::<Ask>().await;
+    assert!(world.request(Ask(1)).await.is_empty());
+    assert_eq!(world.request_first(Ask(1)).await, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn firing_a_request_drops_its_responses() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Asked::default()).await;
+    world.add_event_handler(double).await;
+
+    world.fire_event(Ask(1), true).await;
+    assert_eq!(world.get_resource::<Asked>().await.unwrap().0, 1);
+    assert_eq!(world.request(Ask(1)).await, [2]);
+}
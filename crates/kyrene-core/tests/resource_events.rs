@@ -0,0 +1,89 @@
+mod common;
+
+use kyrene_core::{
+    handler::{ResChanged, ResMut},
+    prelude::*,
+    resource::{ResourceChanged, ResourceInserted, ResourceRemoved},
+};
+
+struct Score(u32);
+
+#[derive(Debug, Default, PartialEq)]
+struct Log(Vec<&'static str>);
+
+async fn on_insert(_event: Event<ResourceInserted<Score>>, mut log: ResMut<Log>) {
+    log.0.push("inserted");
+}
+
+async fn on_remove(_event: Event<ResourceRemoved<Score>>, mut log: ResMut<Log>) {
+    log.0.push("removed");
+}
+
+async fn on_change(_event: Event<ResourceChanged<Score>>, mut log: ResMut<Log>) {
+    log.0.push("changed");
+}
+
+struct Poll;
+
+async fn score_changed(_event: Event<Poll>, score: ResChanged<Score>, mut log: ResMut<Log>) {
+    assert!(score.is_changed());
+    log.0.push("polled");
+}
+
+#[test]
+fn changes_are_reported_once_per_tick() {
+    let mut world = World::new();
+    world.add_event_handler(on_insert);
+    world.add_event_handler(on_remove);
+    world.add_event_handler(on_change);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.insert_resource(Log::default()).await;
+        world.insert_resource(Score(0)).await;
+        world.update().await;
+
+        for _ in 0..3 {
+            world.get_resource_mut::<Score>().await.unwrap().0 += 1;
+        }
+        // nothing is reported until the next tick
+        assert_eq!(world.get_resource::<Log>().await.unwrap().0, ["inserted"]);
+        world.update().await;
+        world.update().await;
+
+        world.remove_resource::<Score>().await;
+        assert_eq!(
+            world.get_resource::<Log>().await.unwrap().0,
+            ["inserted", "changed", "removed"]
+        );
+    });
+}
+
+#[test]
+fn res_changed_skips_unchanged_and_missing_resources() {
+    let mut world = World::new();
+    world.add_event::<Poll>();
+    world.add_event_handler(score_changed);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.insert_resource(Log::default()).await;
+        world.fire_event(Poll, true).await;
+
+        world.insert_resource(Score(0)).await;
+        world.fire_event(Poll, true).await;
+        world.fire_event(Poll, true).await;
+
+        // seen right away, without waiting for a tick
+        world.get_resource_mut::<Score>().await.unwrap().0 = 1;
+        world.fire_event(Poll, true).await;
+
+        world.remove_resource::<Score>().await;
+        world.fire_event(Poll, true).await;
+
+        assert_eq!(
+            world.get_resource::<Log>().await.unwrap().0,
+            ["polled", "polled"]
+        );
+    });
+}
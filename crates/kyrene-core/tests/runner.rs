@@ -0,0 +1,111 @@
+mod common;
+
+use kyrene_core::{
+    handler::ResMut,
+    prelude::*,
+    runner::RunConfig,
+    world::{WorldShutdown, WorldStartup},
+};
+use pollster::block_on;
+
+#[derive(Debug, Default)]
+struct Count {
+    startups: u32,
+    ticks: u64,
+    shutdowns: u32,
+}
+
+async fn count_startup(_event: Event<WorldStartup>, mut count: ResMut<Count>) {
+    count.startups += 1;
+}
+
+async fn count_tick(_event: Event<WorldTick>, mut count: ResMut<Count>) {
+    count.ticks += 1;
+}
+
+async fn count_shutdown(_event: Event<WorldShutdown>, mut count: ResMut<Count>) {
+    count.shutdowns += 1;
+}
+
+fn counting_world() -> World {
+    let mut world = World::new();
+    world.add_event_handler(count_startup);
+    world.add_event_handler(count_tick);
+    world.add_event_handler(count_shutdown);
+    block_on(world.insert_resource(Count::default()));
+    world
+}
+
+#[test]
+fn run_headless_stops_after_max_ticks_or_until() {
+    let world = counting_world()
+        .run_headless(RunConfig::new().with_max_ticks(5))
+        .unwrap();
+    assert_eq!(world.current_tick(), 5);
+    {
+        let count = block_on(world.get_resource::<Count>()).unwrap();
+        assert_eq!((count.startups, count.ticks, count.shutdowns), (1, 5, 1));
+    }
+
+    let world = world
+        .run_headless(
+            RunConfig::new()
+                .with_tick_rate(1000.0)
+                .until(
+                    |world| async move { world.get_resource::<Count>().await.unwrap().ticks >= 8 },
+                ),
+        )
+        .unwrap();
+    assert_eq!(world.current_tick(), 8);
+}
+
+#[test]
+fn update_starts_up_once() {
+    let mut world = counting_world();
+    for _ in 0..3 {
+        world.update().unwrap();
+    }
+
+    assert_eq!(world.current_tick(), 3);
+    let count = block_on(world.get_resource::<Count>()).unwrap();
+    assert_eq!((count.startups, count.ticks, count.shutdowns), (1, 3, 0));
+}
+
+struct Stash(#[allow(dead_code)] WorldHandle);
+
+async fn stash_handle(_event: Event<WorldStartup>, world: WorldHandle) {
+    world.insert_resource(Stash(world.clone())).await;
+}
+
+#[test]
+fn a_leaked_handle_is_an_error() {
+    let mut world = World::new();
+    world.add_event_handler(stash_handle);
+
+    let Err(error) = world.run_headless(RunConfig::new().with_max_ticks(2)) else {
+        panic!("the world was handed back while a handle to it was alive");
+    };
+
+    // the world is still there, behind the leaked handle
+    common::runtime().block_on(async move {
+        let world = error.0;
+        assert!(world.has_resource::<Stash>().await);
+        world.clear_resources().await;
+    });
+}
+
+#[test]
+#[should_panic = "finite and positive"]
+fn zero_tick_rate_is_rejected() {
+    RunConfig::new().with_tick_rate(0.0);
+}
+
+#[test]
+#[should_panic = "finite and positive"]
+fn invalid_tick_rate_fields_are_rejected() {
+    let config = RunConfig {
+        tick_rate: Some(f64::INFINITY),
+        ..RunConfig::new().with_max_ticks(1)
+    };
+    let _ = World::new().run_headless(config);
+}
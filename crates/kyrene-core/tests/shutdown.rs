@@ -0,0 +1,142 @@
+use std::{sync::Mutex, time::Duration};
+
+use kyrene_core::{
+    handler::ResMut,
+    prelude::*,
+    runner::RunConfig,
+    world::{WorldShutdown, WorldStartup},
+};
+use pollster::block_on;
+
+async fn exit_on_third_tick(event: Event<WorldTick>, world: WorldHandle) {
+    if event.tick == 3 {
+        world.fire_event(AppExit::Error(2), false).await;
+    }
+}
+
+#[test]
+fn app_exit_stops_the_run_with_its_code() {
+    let mut world = World::new();
+    world.add_event_handler(exit_on_third_tick);
+
+    let world = world
+        .run_headless(RunConfig::new().with_max_ticks(100))
+        .unwrap();
+    assert!(world.current_tick() <= 5, "{}", world.current_tick());
+    assert_eq!(world.exit_requested(), Some(AppExit::Error(2)));
+}
+
+#[test]
+fn error_zero_is_still_a_failure() {
+    assert_eq!(AppExit::Success.code(), 0);
+    assert_eq!(AppExit::Error(0).code(), 1);
+    assert_eq!(AppExit::Error(3).code(), 3);
+    assert!(!AppExit::Error(0).is_success());
+}
+
+#[derive(Default)]
+struct Finished(u32);
+
+async fn detached_work(_event: Event<WorldShutdown>, mut finished: ResMut<Finished>) {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    finished.0 += 1;
+}
+
+async fn fire_detached(_event: Event<WorldTick>, world: WorldHandle) {
+    world.fire_event(WorldShutdown, false).await;
+}
+
+#[test]
+fn shutdown_waits_for_detached_handlers() {
+    let mut world = World::new();
+    world.add_event_handler(detached_work);
+    world.add_event_handler(fire_detached);
+    block_on(world.insert_resource(Finished::default()));
+
+    let world = world
+        .run_headless(RunConfig::new().with_max_ticks(1))
+        .unwrap();
+    // once from the detached fire, once from shutdown itself
+    assert_eq!(block_on(world.get_resource::<Finished>()).unwrap().0, 2);
+    assert_eq!(world.exit_requested(), None);
+}
+
+static DROPPED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+struct Tracked(&'static str);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.lock().unwrap().push(self.0);
+    }
+}
+
+struct First(#[allow(dead_code)] Tracked);
+struct Second(#[allow(dead_code)] Tracked);
+struct Third(#[allow(dead_code)] Tracked);
+
+#[test]
+fn resources_are_dropped_newest_first() {
+    let mut world = World::new();
+    block_on(async {
+        world.insert_resource(First(Tracked("first"))).await;
+        world.insert_resource(Second(Tracked("second"))).await;
+        world.insert_resource(Third(Tracked("third"))).await;
+        // replacing counts as inserting it again
+        drop(world.insert_resource(First(Tracked("first again"))).await);
+        DROPPED.lock().unwrap().clear();
+
+        world.clear_resources().await;
+    });
+
+    assert_eq!(*DROPPED.lock().unwrap(), ["first again", "third", "second"]);
+}
+
+async fn add_handler_at_startup(_event: Event<WorldStartup>, world: WorldHandle) {
+    world.add_event_handler(count_ticks).await;
+}
+
+async fn count_ticks(_event: Event<WorldTick>, mut finished: ResMut<Finished>) {
+    finished.0 += 1;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handlers_can_be_added_from_async_code() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Finished::default()).await;
+    world.add_event_handler(add_handler_at_startup).await;
+
+    world.startup().await;
+    world.update().await;
+    world.update().await;
+    assert_eq!(world.get_resource::<Finished>().await.unwrap().0, 2);
+}
+
+async fn add_handler_on_first_tick(event: Event<WorldTick>, world: WorldHandle) {
+    if event.tick == 1 {
+        world.add_event_handler(count_ticks).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handlers_can_be_added_while_their_event_is_firing() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Finished::default()).await;
+    world.add_event_handler(add_handler_on_first_tick).await;
+
+    for _ in 0..3 {
+        world.update().await;
+    }
+    // the fire that added it doesn't run it
+    assert_eq!(world.get_resource::<Finished>().await.unwrap().0, 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn handlers_can_be_added_on_a_single_thread() {
+    let world = World::new().into_world_handle();
+    world.insert_resource(Finished::default()).await;
+    world.add_event_handler(count_ticks).await;
+
+    world.update().await;
+    assert_eq!(world.get_resource::<Finished>().await.unwrap().0, 1);
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use kyrene_core::{
+    handler::ResMut,
+    prelude::*,
+    runner::DEFAULT_TICK_RATE,
+    time::{FixedTime, FixedUpdate, Time},
+};
+
+#[test]
+fn time_scales_and_pauses() {
+    let mut time = Time::default();
+    let now = Instant::now();
+    assert_eq!(time.update(now), Duration::ZERO);
+
+    time.set_time_scale(2.0);
+    assert_eq!(
+        time.update(now + Duration::from_millis(10)),
+        Duration::from_millis(20)
+    );
+    time.pause();
+    assert_eq!(time.update(now + Duration::from_millis(20)), Duration::ZERO);
+    assert_eq!(time.raw_delta(), Duration::from_millis(10));
+    assert_eq!(time.elapsed(), Duration::from_millis(20));
+    assert_eq!(time.frame_count(), 3);
+}
+
+#[test]
+fn fixed_time_catches_up_by_at_most_max_steps() {
+    let mut fixed_time = FixedTime::new(Duration::from_millis(10)).with_max_steps(3);
+    assert_eq!(fixed_time.accumulate(Duration::from_millis(25)), 2);
+    assert_eq!(fixed_time.accumulator(), Duration::from_millis(5));
+
+    assert_eq!(fixed_time.accumulate(Duration::from_millis(100)), 3);
+    assert!(fixed_time.accumulator() < Duration::from_millis(10));
+    assert_eq!(fixed_time.steps(), 5);
+}
+
+#[test]
+#[should_panic = "finite and positive"]
+fn zero_hz_is_rejected() {
+    FixedTime::from_hz(0.0);
+}
+
+#[test]
+#[should_panic = "finite and positive"]
+fn negative_hz_is_rejected() {
+    FixedTime::from_hz(-30.0);
+}
+
+#[test]
+#[should_panic = "finite and positive"]
+fn nan_hz_is_rejected() {
+    FixedTime::from_hz(f64::NAN);
+}
+
+#[test]
+#[should_panic = "at least 1"]
+fn zero_max_steps_is_rejected() {
+    FixedTime::from_hz(60.0).with_max_steps(0);
+}
+
+#[test]
+fn falling_behind_is_not_logged_every_tick() {
+    let (logs, _subscriber) = common::capture_logs();
+
+    let mut fixed_time = FixedTime::new(Duration::from_millis(10)).with_max_steps(1);
+    for _ in 0..100 {
+        assert_eq!(fixed_time.accumulate(Duration::from_millis(50)), 1);
+    }
+
+    assert_eq!(logs.contents().matches("fell behind").count(), 1);
+}
+
+#[derive(Default)]
+struct Steps(Vec<u64>);
+
+async fn record_step(event: Event<FixedUpdate>, mut steps: ResMut<Steps>) {
+    steps.0.push(event.step);
+}
+
+#[test]
+fn fixed_updates_fire_in_order() {
+    let mut world = World::new();
+    world.add_event_handler(record_step);
+    let world = world.into_world_handle();
+
+    common::runtime().block_on(async move {
+        world.insert_resource(Steps::default()).await;
+        world
+            .insert_resource(FixedTime::from_hz(1000.0).with_max_steps(100))
+            .await;
+
+        for _ in 0..3 {
+            world.update().await;
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let steps = world.get_resource::<Steps>().await.unwrap().0.clone();
+        let total = world.get_resource::<FixedTime>().await.unwrap().steps();
+        assert!(total >= 10, "only {total} steps in at least 10ms");
+        assert_eq!(steps, (0..total).collect::<Vec<_>>());
+    });
+}
+
+async fn exit_after_ten(event: Event<WorldTick>, world: WorldHandle) {
+    if event.tick == 10 {
+        world.fire_event(AppExit::Success, true).await;
+    }
+}
+
+#[test]
+fn run_is_paced() {
+    let mut world = World::new();
+    world.add_event_handler(exit_after_ten);
+
+    let start = Instant::now();
+    assert_eq!(world.run(), AppExit::Success);
+    // the first tick isn't delayed
+    assert!(start.elapsed() >= Duration::from_secs_f64(9.0 / DEFAULT_TICK_RATE));
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use kyrene_core::{handler::ResMut, prelude::*, time::Time};
+
+struct Ping;
+
+#[derive(Default)]
+struct Pings(u32);
+
+async fn count_ping(_event: Event<Ping>, mut pings: ResMut<Pings>) {
+    pings.0 += 1;
+}
+
+async fn timer_world() -> WorldHandle {
+    let world = World::new().into_world_handle();
+    world.add_event_handler(count_ping).await;
+    world.insert_resource(Pings::default()).await;
+    // the first tick only starts the clock
+    world.update().await;
+    world
+}
+
+/// Sleeps for `duration`, then runs a tick, returning how many pings it fired.
+async fn tick_after(world: &WorldHandle, duration: Duration) -> u32 {
+    let before = world.get_resource::<Pings>().await.unwrap().0;
+    tokio::time::sleep(duration).await;
+    world.update().await;
+    world.get_resource::<Pings>().await.unwrap().0 - before
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn one_shot_timers_fire_once() {
+    let world = timer_world().await;
+    let timer = world
+        .fire_event_after(Ping, Duration::from_millis(10))
+        .await;
+
+    assert_eq!(tick_after(&world, Duration::ZERO).await, 0);
+    assert_eq!(tick_after(&world, Duration::from_millis(20)).await, 1);
+    assert!(timer.is_finished());
+    assert_eq!(tick_after(&world, Duration::from_millis(20)).await, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn repeating_timers_catch_up() {
+    let world = timer_world().await;
+    let timer = world
+        .fire_event_every(Ping, Duration::from_millis(10))
+        .await;
+
+    let fired = tick_after(&world, Duration::from_millis(35)).await;
+    assert!((3..=8).contains(&fired), "{fired}");
+
+    timer.cancel();
+    assert_eq!(tick_after(&world, Duration::from_millis(20)).await, 0);
+    assert!(timer.is_finished());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn catching_up_is_capped() {
+    let world = timer_world().await;
+    let default = world.fire_event_every(Ping, Duration::from_millis(5)).await;
+    assert_eq!(default.max_catch_up(), 8);
+    let world_time = world.get_resource::<Time>().await.unwrap().elapsed();
+
+    assert_eq!(tick_after(&world, Duration::from_millis(100)).await, 8);
+    let elapsed = world.get_resource::<Time>().await.unwrap().elapsed() - world_time;
+    assert!(elapsed >= Duration::from_millis(100));
+    // what was dropped isn't made up for later
+    world.get_resource_mut::<Time>().await.unwrap().pause();
+    assert_eq!(tick_after(&world, Duration::ZERO).await, 0);
+    world.get_resource_mut::<Time>().await.unwrap().unpause();
+    default.cancel();
+
+    world
+        .fire_event_every(Ping, Duration::from_millis(5))
+        .await
+        .with_max_catch_up(3);
+    assert_eq!(tick_after(&world, Duration::from_millis(100)).await, 3);
+}
+
+#[test]
+#[should_panic = "at least 1"]
+fn zero_catch_up_is_rejected() {
+    kyrene_core::timer::TimerHandle::default().with_max_catch_up(0);
+}
@@ -6,7 +6,7 @@ use kyrene_core::{
     handler::{Res, ResMut},
     plugin::Plugin,
     prelude::{Component, StreamExt, WorldHandle},
-    query::Query,
+    query::{Added, Query},
     util::TypeIdMap,
 };
 
@@ -70,7 +70,7 @@ pub async fn create_component_bind_group<T: CreateBindGroup>(
     world: WorldHandle,
     device: Res<Device>,
     mut layouts: ResMut<BindGroupLayouts>,
-    item_query: Query<(Entity, &T)>,
+    item_query: Query<(Entity, &T), Added<T>>,
 ) {
     let mut item_query = item_query.iter();
     while let Some((entity, item)) = item_query.next().await {
@@ -9,6 +9,7 @@ use clear_color::ClearColorPlugin;
 use hdr::HdrPlugin;
 use kyrene_core::{
     entity::Entity,
+    error::HandlerError,
     event::Event,
     handler::{Res, ResMut},
     plugin::Plugin,
@@ -304,9 +305,9 @@ pub async fn begin_render(
     surface: Res<WindowSurface>,
     device: Res<Device>,
     mut command_buffers: ResMut<CommandBuffers>,
-) {
+) -> Result<(), HandlerError> {
     if current_frame.inner.is_some() {
-        return;
+        return Ok(());
     }
 
     tracing::trace!("begin_render");
@@ -316,12 +317,9 @@ pub async fn begin_render(
         world.remove::<ViewTarget>(entity).await;
     }
 
-    let frame = match surface.get_current_texture() {
-        Ok(frame) => frame,
-        Err(e) => {
-            panic!("Failed to acquire next surface texture: {}", e);
-        }
-    };
+    let frame = surface
+        .get_current_texture()
+        .map_err(|e| format!("Failed to acquire next surface texture: {e}"))?;
 
     let depth_texture = world.get_resource::<DepthTexture>().await.unwrap();
 
@@ -375,6 +373,8 @@ pub async fn begin_render(
     world
         .insert_resource(ActiveCommandEncoder { encoder })
         .await;
+
+    Ok(())
 }
 
 pub struct EndRender;
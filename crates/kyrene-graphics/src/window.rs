@@ -1,4 +1,10 @@
-use std::{ops::Deref, sync::Arc};
+use std::{
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use kyrene_core::{
     event::Event,
@@ -7,7 +13,8 @@ use kyrene_core::{
         tokio::{self, sync::mpsc},
         World, WorldHandle,
     },
-    world::{WorldShutdown, WorldStartup, WorldTick},
+    runner::{AppExit, DEFAULT_SHUTDOWN_TIMEOUT},
+    world::WorldShutdown,
 };
 use tracing_subscriber::EnvFilter;
 use winit::{
@@ -64,18 +71,23 @@ pub struct WindowResized {
 }
 
 pub trait RunWindow {
-    fn run_window(self, window_settings: WindowSettings);
+    /// Runs the world until the window is closed or an [`AppExit`] is fired, returning the exit.
+    fn run_window(self, window_settings: WindowSettings) -> AppExit;
 }
 
 impl RunWindow for World {
-    fn run_window(self, window_settings: WindowSettings) {
+    fn run_window(self, window_settings: WindowSettings) -> AppExit {
         let event_loop = winit::event_loop::EventLoop::new().unwrap();
 
         let world = self.into_world_handle();
 
         let (tx, rx) = winit_events_channel();
 
-        std::thread::spawn({
+        // set once the world has stopped, so the event loop knows to follow
+        let world_stopped = Arc::new(AtomicBool::new(false));
+
+        let runtime_thread = std::thread::spawn({
+            let world_stopped = world_stopped.clone();
             let world = world.clone();
             let window_settings = window_settings.clone();
             move || {
@@ -111,20 +123,7 @@ impl RunWindow for World {
                 runtime.block_on(async move {
                     world.insert_resource(window_settings).await;
 
-                    world.fire_event(WorldStartup, true).await;
-
-                    // spawn WorldTick task
-                    let mut tick = 0;
-                    tokio::spawn({
-                        let world = world.clone();
-                        async move {
-                            loop {
-                                tick += 1;
-                                world.fire_event(WorldTick { tick }, true).await;
-                                tokio::task::yield_now().await;
-                            }
-                        }
-                    });
+                    world.startup().await;
 
                     let WinitEventsRx {
                         mut window_created,
@@ -159,19 +158,23 @@ impl RunWindow for World {
                     tokio::spawn({
                         let world = world.clone();
                         async move {
-                            loop {
-                                let Some(()) = exiting.recv().await else {
-                                    return;
-                                };
-                                world.fire_event(WorldShutdown, true).await;
-                            }
+                            let Some(()) = exiting.recv().await else {
+                                return;
+                            };
+                            world.fire_event(AppExit::Success, true).await;
                         }
                     });
 
-                    loop {
+                    while world.exit_requested().await.is_none() {
+                        world.update().await;
                         tokio::task::yield_now().await;
                     }
-                });
+                    world_stopped.store(true, Ordering::Release);
+
+                    let exit = world.shutdown(DEFAULT_SHUTDOWN_TIMEOUT).await;
+                    world.clear_resources().await;
+                    exit
+                })
             }
         });
 
@@ -179,9 +182,14 @@ impl RunWindow for World {
             window: None,
             window_settings,
             events: tx,
+            world_stopped,
         };
 
         event_loop.run_app(&mut winit_app).unwrap();
+
+        // the event loop's channels are closed now, so this only waits for the world to shut down
+        drop(winit_app);
+        runtime_thread.join().unwrap()
     }
 }
 
@@ -272,6 +280,7 @@ struct WinitApp {
     window: Option<Window>,
     window_settings: WindowSettings,
     events: WinitEventsTx,
+    world_stopped: Arc<AtomicBool>,
 }
 
 impl winit::application::ApplicationHandler for WinitApp {
@@ -344,13 +353,14 @@ impl winit::application::ApplicationHandler for WinitApp {
         device_id: winit::event::DeviceId,
         event: winit::event::DeviceEvent,
     ) {
-        self.events
-            .winit_event
-            .blocking_send(WinitEvent(winit::event::Event::DeviceEvent {
-                device_id,
-                event: event.clone(),
-            }))
-            .unwrap();
+        // the world may have stopped already, and stopped listening with it
+        let _ =
+            self.events
+                .winit_event
+                .blocking_send(WinitEvent(winit::event::Event::DeviceEvent {
+                    device_id,
+                    event: event.clone(),
+                }));
     }
 
     fn window_event(
@@ -361,13 +371,14 @@ impl winit::application::ApplicationHandler for WinitApp {
     ) {
         event_loop.set_control_flow(ControlFlow::Poll);
 
-        self.events
-            .winit_event
-            .blocking_send(WinitEvent(winit::event::Event::WindowEvent {
-                window_id,
-                event: event.clone(),
-            }))
-            .unwrap();
+        // the world may have stopped already, and stopped listening with it
+        let _ =
+            self.events
+                .winit_event
+                .blocking_send(WinitEvent(winit::event::Event::WindowEvent {
+                    window_id,
+                    event: event.clone(),
+                }));
 
         if let Some(window) = self.window.as_ref() {
             if window.id() != window_id {
@@ -386,7 +397,13 @@ impl winit::application::ApplicationHandler for WinitApp {
         }
     }
 
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        if self.world_stopped.load(Ordering::Acquire) {
+            event_loop.exit();
+        }
+    }
+
     fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
-        self.events.exiting.blocking_send(()).unwrap();
+        let _ = self.events.exiting.blocking_send(());
     }
 }
@@ -30,12 +30,42 @@ pub fn derive_bundle(input: TokenStream) -> TokenStream {
 
     quote! {
         impl #ig kyrene_core::bundle::Bundle for #ident #tg #wc {
-            fn into_dyn_components(self) -> Vec<(kyrene_core::util::TypeInfo, Box<dyn kyrene_core::component::Component>)> {
+            fn into_dyn_components(self) -> Vec<(kyrene_core::component::ComponentInfo, Box<dyn kyrene_core::component::Component>)> {
                 vec![#(
-                    (kyrene_core::util::TypeInfo::of::<#member_types>(), Box::new(self.#members))
+                    (kyrene_core::component::ComponentInfo::of::<#member_types>(), Box::new(self.#members))
                 ),*]
             }
         }
     }
     .into()
 }
+
+#[proc_macro_derive(HandlerSet)]
+pub fn derive_handler_set(input: TokenStream) -> TokenStream {
+    let input: syn::DeriveInput = match syn::parse(input) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let ident = &input.ident;
+    let (ig, tg, wc) = &input.generics.split_for_impl();
+
+    quote! {
+        impl #ig kyrene_core::handler_set::HandlerSet for #ident #tg #wc {
+            fn dyn_clone(&self) -> Box<dyn kyrene_core::handler_set::HandlerSet> {
+                Box::new(::core::clone::Clone::clone(self))
+            }
+
+            fn as_dyn_eq(&self) -> &dyn kyrene_core::label::DynEq {
+                self
+            }
+
+            fn dyn_hash(&self, mut state: &mut dyn ::core::hash::Hasher) {
+                let type_id = ::core::any::TypeId::of::<Self>();
+                ::core::hash::Hash::hash(&type_id, &mut state);
+                ::core::hash::Hash::hash(self, &mut state);
+            }
+        }
+    }
+    .into()
+}
@@ -1,3 +1,5 @@
+use std::process::ExitCode;
+
 use kyrene::prelude::*;
 use kyrene_core::{handler::Local, world::WorldStartup};
 use kyrene_graphics::{
@@ -48,7 +50,7 @@ async fn print_frame_time(event: Event<RedrawRequested>, info: Local<FrameTime>)
     }
 }
 
-fn main() {
+fn main() -> ExitCode {
     let mut world = World::new();
     world.add_plugin(WinitPlugin);
     world.add_plugin(WgpuPlugin);
@@ -57,5 +59,5 @@ fn main() {
     world.add_event_handler(print_frame_time);
     world.add_event_handler(world_tick);
 
-    world.run_window(WindowSettings::default());
+    world.run_window(WindowSettings::default()).into()
 }